@@ -0,0 +1,89 @@
+"""Time PostPyro's ways of reading a large result, for the README tables.
+
+Usage:
+    python benchmarks/read_rows.py postgresql://user@localhost/db
+
+Creates (once) a 100,000-row table named bench_read_rows, then runs every
+method in a fresh process so that peak memory growth, the rise in maximum
+RSS over the process's footprint after connecting, isn't shared between
+them. Times are the median of RUNS runs. Build PostPyro in release mode
+(`maturin develop --release`) before comparing numbers.
+"""
+
+import resource
+import statistics
+import subprocess
+import sys
+import time
+
+import PostPyro as pg
+
+ROWS = 100_000
+RUNS = 5
+
+SETUP = f"""
+CREATE TABLE IF NOT EXISTS bench_read_rows AS
+SELECT g AS id,
+       'row number ' || g AS name,
+       (g * 1.25)::numeric(12, 2) AS amount,
+       timestamptz '2024-01-01' + g * interval '1 minute' AS created_at,
+       g % 2 = 0 AS active
+FROM generate_series(1, {ROWS}) AS g
+"""
+
+# int, text, numeric and timestamptz columns
+QUERY = "SELECT id, name, amount, created_at FROM bench_read_rows"
+# The same plus a bool column
+DICT_QUERY = "SELECT id, name, amount, created_at, active FROM bench_read_rows"
+
+
+def stream(conn, batch_size):
+    return sum(1 for _ in conn.query_stream(QUERY, batch_size=batch_size))
+
+
+METHODS = {
+    "query": lambda conn: len(conn.query(QUERY)),
+    "query_stream(batch_size=100)": lambda conn: stream(conn, 100),
+    "query_stream(batch_size=1000)": lambda conn: stream(conn, 1000),
+    "[r.as_dict() for r in conn.query(q)]": lambda conn: len([r.as_dict() for r in conn.query(DICT_QUERY)]),
+    "conn.query_dict(q)": lambda conn: len(conn.query_dict(DICT_QUERY)),
+}
+
+
+def max_rss_mb():
+    # ru_maxrss is in KiB on Linux
+    return resource.getrusage(resource.RUSAGE_SELF).ru_maxrss / 1024
+
+
+def run_method(dsn, name):
+    conn = pg.Connection(dsn)
+    method = METHODS[name]
+    baseline = max_rss_mb()
+    times = []
+    for _ in range(RUNS):
+        start = time.perf_counter()
+        count = method(conn)
+        times.append(time.perf_counter() - start)
+        assert count == ROWS, count
+    print(f"{statistics.median(times) * 1000:.0f} {max_rss_mb() - baseline:.0f}")
+
+
+def main(dsn):
+    pg.Connection(dsn).execute(SETUP)
+    print(f"| {'Method':<40} | {'Time':>7} | {'Peak memory growth':>18} |")
+    print(f"| {'-' * 40} | {'-' * 7} | {'-' * 18} |")
+    for name in METHODS:
+        out = subprocess.run(
+            [sys.executable, __file__, dsn, name], check=True, capture_output=True, text=True
+        ).stdout
+        ms, mb = out.split()
+        print(f"| {'`' + name + '`':<40} | {ms + ' ms':>7} | {mb + ' MB':>18} |")
+
+
+if __name__ == "__main__":
+    if len(sys.argv) == 3:
+        run_method(sys.argv[1], sys.argv[2])
+    elif len(sys.argv) == 2:
+        main(sys.argv[1])
+    else:
+        sys.exit(__doc__)
@@ -4,7 +4,8 @@ Type stubs for pypg_driver - High-performance PostgreSQL driver for Python.
 This file provides type hints for IDE support and static type checking.
 """
 
-from typing import Any, Dict, List, Optional, Union, Iterator, Tuple
+from typing import Any, Dict, Iterable, List, Optional, Sequence, Union, Iterator, Tuple, Literal, overload
+import array
 import datetime
 import uuid
 
@@ -12,10 +13,43 @@ __version__: str
 apilevel: str
 threadsafety: int
 paramstyle: str
+"""Default placeholder style: "numeric", meaning PostgreSQL's ``$1``, not PEP 249's ``:1``."""
+
+Params = Union[List[Any], Tuple[Any, ...], Dict[str, Any]]
+"""Query parameters: a list for ``$N`` placeholders; with ``paramstyle="pyformat"`` also a tuple or dict."""
 
 class DatabaseError(Exception):
     """Base exception for all database-related errors."""
-    pass
+
+    pgcode: Optional[str]
+    """Five-character SQLSTATE (e.g. "40001"), None if the error didn't come from the server."""
+    pgerror: Optional[str]
+    """The server's error message, None if the error didn't come from the server."""
+    detail: Optional[str]
+    """The server's DETAIL line, e.g. "Key (email)=(a@b.c) already exists."."""
+    hint: Optional[str]
+    """The server's HINT line, if any."""
+    position: Optional[int]
+    """1-based character offset of the error in the query text, if reported."""
+    table_name: Optional[str]
+    """Table the error relates to, if reported."""
+    column_name: Optional[str]
+    """Column the error relates to, if reported."""
+    constraint_name: Optional[str]
+    """Constraint that was violated, e.g. "users_email_key" for a unique violation."""
+
+    def __init__(
+        self,
+        message: Optional[str] = None,
+        pgcode: Optional[str] = None,
+        pgerror: Optional[str] = None,
+        detail: Optional[str] = None,
+        hint: Optional[str] = None,
+        position: Optional[int] = None,
+        table_name: Optional[str] = None,
+        column_name: Optional[str] = None,
+        constraint_name: Optional[str] = None,
+    ) -> None: ...
 
 class InterfaceError(DatabaseError):
     """Exception for client-side errors (connection, etc.)."""
@@ -45,6 +79,14 @@ class NotSupportedError(DatabaseError):
     """Exception for unsupported operations."""
     pass
 
+class NoDataError(DataError):
+    """Raised by query_one when the query returned no rows."""
+    pass
+
+class TooManyRowsError(DataError):
+    """Raised by query_one when the query returned more than one row."""
+    pass
+
 class Row:
     """Represents a single row from a query result."""
 
@@ -52,20 +94,32 @@ class Row:
         """Return the number of columns in this row."""
         ...
 
-    def __getitem__(self, key: Union[int, str]) -> Any:
-        """Get a column value by index (int) or name (str)."""
+    def __getitem__(self, key: Union[int, str, slice]) -> Any:
+        """Get a column value by index (int, negative from the end) or name (str), or a tuple of values by slice."""
         ...
 
     def __iter__(self) -> Iterator[Any]:
         """Iterate over column values."""
         ...
 
+    def __contains__(self, item: Any) -> bool:
+        """True if item equals a column value, as for a tuple; names aren't matched."""
+        ...
+
     def __repr__(self) -> str:
-        """String representation for debugging."""
+        """``Row(id=1, name='alice')``, for debugging."""
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        """Equal to a Row with the same columns and values, or a tuple/list of the values."""
+        ...
+
+    def __hash__(self) -> int:
+        """Hash of the values; TypeError if one is unhashable."""
         ...
 
     def get(self, key: Union[int, str], default: Any = None) -> Any:
-        """Get a column value with a default if not found."""
+        """Get a column value, or default if the row has no such column (a NULL column returns None)."""
         ...
 
     def keys(self) -> List[str]:
@@ -80,31 +134,132 @@ class Row:
         """Return a list of (name, value) tuples."""
         ...
 
+    def column_origins(self) -> List[Optional[Tuple[int, int]]]:
+        """(table_oid, column position) each column was read from, or None."""
+        ...
+
+    def as_dict(self) -> Dict[str, Any]:
+        """Convert the row to a dictionary; duplicate column names keep the last value."""
+        ...
+
     def to_dict(self) -> Dict[str, Any]:
-        """Convert the row to a dictionary."""
+        """Convert the row to a dictionary (alias of as_dict)."""
+        ...
+
+class PreparedStatement:
+    """Handle to a statement prepared on a connection."""
+
+    sql: str
+
+    @property
+    def param_types(self) -> List[str]:
+        """Parameter type names inferred by the server."""
+        ...
+
+    def execute(
+        self, params: Optional[Params] = None, statement_timeout_ms: Optional[int] = None
+    ) -> int:
+        """Execute the statement without returning rows."""
+        ...
+
+    def query(
+        self, params: Optional[Params] = None, statement_timeout_ms: Optional[int] = None
+    ) -> List[Row]:
+        """Execute the statement and return all rows."""
+        ...
+
+    def query_one(
+        self, params: Optional[Params] = None, statement_timeout_ms: Optional[int] = None
+    ) -> Row:
+        """Execute the statement and return exactly one row."""
+        ...
+
+class ReusedRowIterator:
+    """Iterator that yields the same list, overwritten, for every row."""
+
+    def __iter__(self) -> "ReusedRowIterator": ...
+    def __next__(self) -> List[Any]: ...
+
+class CopyOutIterator:
+    """Chunks of ``COPY ... TO STDOUT`` output, read from the server as it advances."""
+
+    def close(self) -> None:
+        """Stop the export early, discarding the rest of the output."""
+        ...
+
+    def __iter__(self) -> "CopyOutIterator": ...
+    def __next__(self) -> bytes: ...
+    def __enter__(self) -> "CopyOutIterator": ...
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> bool: ...
+
+class Cursor:
+    """Server-side cursor that fetches a large result in batches."""
+
+    name: str
+    fetch_size: int
+    scroll: bool
+
+    def fetchmany(self, size: Optional[int] = None) -> List[Row]:
+        """Fetch up to ``size`` rows (default fetch_size); empty once exhausted."""
+        ...
+
+    def fetchone(self) -> Optional[Row]:
+        """Fetch the next row, or None once exhausted."""
+        ...
+
+    def fetch_backward(self, count: int = 1) -> List[Row]:
+        """Fetch the rows before the current position, nearest first (scroll cursors only)."""
+        ...
+
+    def fetch_absolute(self, position: int) -> Optional[Row]:
+        """Fetch the row at a 1-based position, negative from the end (scroll cursors only)."""
+        ...
+
+    def close(self) -> None:
+        """Close the cursor, committing the transaction it opened if any."""
+        ...
+
+    def is_closed(self) -> bool:
+        """Check if the cursor is closed."""
+        ...
+
+    def __iter__(self) -> "Cursor": ...
+    def __next__(self) -> Row: ...
+    def __enter__(self) -> "Cursor": ...
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> bool: ...
+
+class QueryResult:
+    """Rows and affected-row count of a single statement."""
+
+    rows: List[Row]
+    rowcount: int
+    truncated: bool
+
+    def __len__(self) -> int:
+        """Return the number of rows returned."""
         ...
 
 class Transaction:
     """Represents a database transaction."""
 
-    def execute(self, query: str, params: Optional[List[Any]] = None) -> int:
+    def execute(self, query: str, params: Optional[Params] = None) -> int:
         """Execute a query within the transaction that doesn't return rows."""
         ...
 
-    def query(self, query: str, params: Optional[List[Any]] = None) -> List[Row]:
+    def query(self, query: str, params: Optional[Params] = None) -> List[Row]:
         """Execute a query within the transaction and return all rows."""
         ...
 
-    def query_one(self, query: str, params: Optional[List[Any]] = None) -> Row:
+    def query_one(self, query: str, params: Optional[Params] = None) -> Row:
         """Execute a query within the transaction and return exactly one row."""
         ...
 
-    def commit(self) -> None:
-        """Commit the transaction."""
+    def commit(self, chain: bool = False) -> None:
+        """Commit the transaction; ``chain=True`` starts a new one with the same settings."""
         ...
 
-    def rollback(self) -> None:
-        """Roll back the transaction."""
+    def rollback(self, chain: bool = False) -> None:
+        """Roll back the transaction; ``chain=True`` starts a new one with the same settings."""
         ...
 
     def savepoint(self, name: str) -> None:
@@ -115,61 +270,463 @@ class Transaction:
         """Roll back to a savepoint."""
         ...
 
+    def set_local(self, param: str, value: str) -> None:
+        """Set a configuration parameter (e.g. role) until the transaction ends."""
+        ...
+
     def __enter__(self) -> 'Transaction':
         """Context manager entry."""
         ...
 
-    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
-        """Context manager exit - auto-rollback on exception."""
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> bool:
+        """Context manager exit - commit on success, roll back on exception."""
         ...
 
 class Connection:
-    """PostgreSQL database connection."""
+    """PostgreSQL database connection.
+
+    Statements run through the query methods are prepared once and cached
+    (up to 500, least recently used evicted). The cache is per connection:
+    prepared statements live in the server session, so each ``Connection``
+    prepares its own, even with the same connection string. Reuse one
+    connection to get the most out of the cache.
+    """
+
+    def __init__(
+        self,
+        connection_string: str,
+        text_format: bool = False,
+        strict: bool = False,
+        default_isolation_level: Optional[str] = None,
+        log_queries: bool = False,
+        redact_params: Optional[List[Union[int, str]]] = None,
+        interval_format: str = "timedelta",
+        json_format: str = "python",
+        text_types: Optional[List[str]] = None,
+        notification_queue_size: int = 10000,
+        notification_overflow: str = "drop_oldest",
+        max_idle: Optional[float] = None,
+        ssl_cert: Optional[str] = None,
+        ssl_key: Optional[str] = None,
+        unnamed_statements: bool = False,
+        return_timezone: Optional[str] = None,
+        connect_timeout: Optional[float] = None,
+        statement_timeout_ms: Optional[int] = None,
+        paramstyle: Literal["numeric", "pyformat"] = "numeric",
+        autocommit: bool = True,
+        auto_reconnect: bool = False,
+        numeric_format: str = "decimal",
+    ) -> None:
+        """Create a new database connection.
+
+        TLS follows the connection string's ``sslmode``: "disable", "prefer"
+        (default), "require", or "verify-full", which also checks the server
+        certificate against ``sslrootcert`` (a PEM file, or "system" for the
+        system's trusted roots, the default), e.g.
+        ``postgresql://user@host/db?sslmode=verify-full&sslrootcert=/path/ca.pem``.
+        For client certificate authentication pass ``ssl_cert`` and ``ssl_key``
+        (PEM files), or ``sslcert`` and ``sslkey`` in the connection string.
+
+        With ``strict=True``, lossy parameter conversions and undecodable
+        column values raise DataError instead of truncating or returning None.
+
+        With ``log_queries=True``, statements are logged at DEBUG level on the
+        "PostPyro" logger. ``redact_params`` lists 1-based parameter positions
+        and/or column name patterns whose values are logged as ``***``. With
+        patterns, a parameter whose column can't be told is hidden as well.
+
+        ``interval_format="iso"`` returns INTERVAL values as ISO 8601 duration
+        strings instead of ``datetime.timedelta``.
+
+        NUMERIC values are returned as ``decimal.Decimal``. With
+        ``numeric_format="int"``, values with no digits after the point
+        (scale 0) are returned as ``int`` instead, so an unconstrained
+        ``numeric`` column can mix both types.
+
+        JSON and JSONB values are parsed into dicts and lists; pass
+        ``json_format="text"`` to get the JSON text instead. dict parameters,
+        and list parameters sent to a JSON/JSONB column, are bound as JSON.
+        A str sent to a JSON/JSONB or JSONPATH parameter is bound as the JSON
+        text or path it holds, e.g. ``data @> $1::jsonb`` or ``data @@ $1``.
+
+        ``text_types`` lists column types ("json", "jsonb", "bytea") returned
+        in their PostgreSQL text form, e.g. ``text_types=["jsonb", "bytea"]``
+        returns JSONB as JSON text and bytea as ``"\\x..."`` hex strings.
+
+        At most ``notification_queue_size`` unconsumed notifications are
+        kept; ``notification_overflow`` ("drop_oldest" or "drop_newest")
+        picks which one is discarded when the queue is full.
+
+        With ``max_idle`` set, a connection idle for more than that many
+        seconds is pinged before its next statement; if it doesn't respond
+        it is closed and OperationalError is raised.
+
+        With ``unnamed_statements=True``, queries run with ``cache=False`` use
+        the unnamed prepared statement: one round trip, nothing cached and no
+        statement left on the server. Parameters are sent as text for the
+        server to type; list parameters (and floats with ``strict=True``)
+        still go through a named statement.
+
+        ``return_timezone`` (an IANA name such as "Europe/Berlin") returns
+        TIMESTAMPTZ values in that zone, with a ``zoneinfo.ZoneInfo`` tzinfo,
+        instead of UTC. The instant is unchanged; only the wall-clock time
+        and tzinfo differ.
+
+        ``connect_timeout`` bounds the whole connection attempt, in seconds.
+        ``statement_timeout_ms`` bounds each statement run through this
+        connection's query methods: on expiry the statement is canceled on
+        the server and OperationalError is raised. ``execute``, ``query`` and
+        ``query_one`` take ``statement_timeout_ms`` to override it per call
+        (0 for no limit).
+
+        With ``paramstyle="pyformat"``, queries may use psycopg2's ``%s``
+        placeholders with a list or tuple of parameters, or ``%(name)s`` with
+        a dict; a literal % is then written ``%%``. Queries without such
+        placeholders given a list still use ``$1``, ``$2``, ...
+        """
+        ...
+
+    @overload
+    def execute(
+        self,
+        query: str,
+        params: Optional[Params] = None,
+        cache: bool = True,
+        returning: Literal[False] = False,
+        statement_timeout_ms: Optional[int] = None,
+        prepare: Optional[bool] = None,
+    ) -> int:
+        """Execute a query that doesn't return rows (INSERT, UPDATE, DELETE).
+
+        ``cache=False`` skips the prepared statement cache, for one-off statements.
+        ``prepare=False`` is an alias for it: the statement is still prepared,
+        just not kept. ``execute``, ``query`` and ``query_one`` all take both.
+        """
+        ...
+
+    @overload
+    def execute(
+        self,
+        query: str,
+        params: Optional[Params] = None,
+        cache: bool = True,
+        *,
+        returning: Literal[True],
+        statement_timeout_ms: Optional[int] = None,
+        prepare: Optional[bool] = None,
+    ) -> List[Row]:
+        """Execute an INSERT/UPDATE/DELETE ... RETURNING and return the rows."""
+        ...
 
-    def __init__(self, connection_string: str) -> None:
-        """Create a new database connection."""
+    def query(
+        self,
+        query: str,
+        params: Optional[Params] = None,
+        text_format: Optional[bool] = None,
+        cache: bool = True,
+        statement_timeout_ms: Optional[int] = None,
+        prepare: Optional[bool] = None,
+    ) -> List[Row]:
+        """Execute a query and return all rows; ``cache=False`` (or ``prepare=False``) skips the statement cache."""
         ...
 
-    def execute(self, query: str, params: Optional[List[Any]] = None) -> int:
-        """Execute a query that doesn't return rows (INSERT, UPDATE, DELETE)."""
+    def query_multi(self, sql: str) -> List[List[Row]]:
+        """Run a multi-statement script and return the rows of each statement that returns rows.
+
+        Uses the simple query protocol: no parameters, and every value is a string or None.
+        """
         ...
 
-    def query(self, query: str, params: Optional[List[Any]] = None) -> List[Row]:
-        """Execute a query and return all rows."""
+    def query_one(
+        self,
+        query: str,
+        params: Optional[Params] = None,
+        text_format: Optional[bool] = None,
+        cache: bool = True,
+        statement_timeout_ms: Optional[int] = None,
+        prepare: Optional[bool] = None,
+    ) -> Row:
+        """Execute a query and return exactly one row; ``cache=False`` (or ``prepare=False``) skips the statement cache."""
         ...
 
-    def query_one(self, query: str, params: Optional[List[Any]] = None) -> Row:
-        """Execute a query and return exactly one row."""
+    def query_arrow(self, query: str, params: Optional[Params] = None) -> Any:
+        """Execute a query and return a ``pyarrow.Table`` (requires pyarrow and the ``arrow`` build feature).
+
+        Columns are built in Rust and passed through the Arrow C Data Interface
+        without copying; types without an Arrow mapping raise NotSupportedError.
+        """
+        ...
+
+    def query_df(self, query: str, params: Optional[Params] = None) -> Any:
+        """Execute a query and return a ``pandas.DataFrame`` (requires pandas).
+
+        Integer, float and bool columns keep their width; NULLs use pandas'
+        nullable ``Int16``/``Int32``/``Int64``/``boolean`` dtypes or NaN.
+        """
         ...
 
-    def prepare(self, query: str) -> str:
-        """Prepare a statement for repeated execution."""
+    def query_dict(self, query: str, params: Optional[Params] = None) -> List[Dict[str, Any]]:
+        """Execute a query and return its rows as plain dicts, without building Row objects."""
+        ...
+
+    def query_with_description(
+        self, query: str, params: Optional[Params] = None
+    ) -> Tuple[List[Row], Optional[List[Tuple[str, int, None, None, None, None, None]]]]:
+        """Execute a query and return (rows, PEP 249 description); type_code is the type OID."""
+        ...
+
+    def query_result(
+        self, query: str, params: Optional[Params] = None, max_rows: Optional[int] = None
+    ) -> QueryResult:
+        """Execute a statement and return both its rows and affected-row count.
+
+        With ``max_rows``, at most that many rows are kept and ``.truncated``
+        is True if the statement returned more.
+        """
+        ...
+
+    def query_columns(
+        self, query: str, params: Optional[Params] = None
+    ) -> Dict[str, Union["array.array[Any]", List[Any]]]:
+        """Return the result column by column.
+
+        Integer and float columns without NULLs are ``array.array`` objects of
+        the column's width ('h', 'i', 'q', 'f', 'd'); other columns are lists.
+        """
+        ...
+
+    def fetchval(
+        self,
+        query: str,
+        params: Optional[Params] = None,
+        column: Union[int, str] = 0,
+        cache: bool = True,
+        statement_timeout_ms: Optional[int] = None,
+    ) -> Any:
+        """Return one value from the first row, or None if there are no rows.
+
+        Only the first row is read. A negative ``column`` counts from the end.
+        """
+        ...
+
+    def query_csv(self, query: str, params: Optional[Params] = None) -> str:
+        """Return the result as RFC 4180 CSV text with a header line."""
+        ...
+
+    def query_json(
+        self, query: str, params: Optional[Params] = None, server_side: bool = False
+    ) -> str:
+        """Return the result as a JSON array of objects keyed by column name."""
+        ...
+
+    def query_iter_reuse(
+        self, query: str, params: Optional[Params] = None
+    ) -> ReusedRowIterator:
+        """Iterate over results, reusing one list for every row.
+
+        The yielded list is overwritten on each iteration; copy it to keep a row.
+        Rows are yielded as they arrive, with the GIL released while waiting.
+        """
+        ...
+
+    def prepare(self, query: str) -> PreparedStatement:
+        """Prepare a statement and return a handle for repeated execution."""
+        ...
+
+    def validate(self, query: str) -> Dict[str, Any]:
+        """Prepare a query without running it and report whether it is valid.
+
+        Returns ``valid``, ``params`` (inferred parameter type names),
+        ``columns`` ((name, type) tuples) and, for invalid queries, the server's
+        ``error``, ``pgcode``, ``position`` and ``hint`` (otherwise None).
+        """
+        ...
+
+    def copy_in(
+        self,
+        table: str,
+        data: Union[bytes, bytearray, str, Iterable[Sequence[Any]]],
+        columns: Optional[List[str]] = None,
+    ) -> int:
+        """Bulk load CSV data or an iterable of row tuples with COPY FROM STDIN."""
+        ...
+
+    def copy_from(
+        self, table: str, columns: Optional[List[str]], rows: Iterable[Sequence[Any]]
+    ) -> int:
+        """Stream an iterable of rows into a table with binary COPY; aborts cleanly on error."""
+        ...
+
+    @overload
+    def copy_to(
+        self, source: str, format: str = "csv", file: None = None, header: bool = False
+    ) -> CopyOutIterator:
+        """Export a table or query; iterate the returned chunks of bytes."""
+        ...
+
+    @overload
+    def copy_to(self, source: str, format: str = "csv", file: Any = ..., header: bool = False) -> int:
+        """Export a table or query into a binary file object; returns bytes written."""
+        ...
+
+    def listen(self, channel: str) -> None:
+        """Subscribe to NOTIFY messages on a channel (case-sensitive)."""
+        ...
+
+    def unlisten(self, channel: Optional[str] = None) -> None:
+        """Unsubscribe from a channel, or from every channel if None."""
+        ...
+
+    def notifications(self, timeout: float = 0.0) -> List[Tuple[str, str, int]]:
+        """Take all pending (channel, payload, pid) notifications, waiting up to ``timeout`` for one."""
+        ...
+
+    def wait_for_notification(self, timeout: Optional[float] = None) -> Optional[Tuple[str, str, int]]:
+        """Block until a NOTIFY arrives on a LISTENed channel; None on timeout."""
+        ...
+
+    def table_columns(
+        self, table_name: str, schema: str = "public"
+    ) -> List[Tuple[str, str, bool, Optional[str]]]:
+        """(name, type, nullable, default) for each column of a table."""
+        ...
+
+    def register_composite(self, type_name: str, as_dict: bool = False) -> List[str]:
+        """Decode a composite type into named tuples (or dicts); returns its field names."""
+        ...
+
+    def notification_stats(self) -> Dict[str, int]:
+        """Pending and dropped (queue full) notification counts."""
+        ...
+
+    def query_stats(self, query: str) -> Optional[Row]:
+        """Return the pg_stat_statements row for a statement that has been run.
+
+        None if pg_stat_statements isn't installed and preloaded, or the
+        statement has no entry. Queries without placeholders are matched by
+        query identifier (PostgreSQL 14+), others by their text.
+        """
+        ...
+
+    def server_prepared_statements(self) -> List[Dict[str, Any]]:
+        """List the prepared statements held server-side for this session."""
+        ...
+
+    def cancel(self) -> None:
+        """Ask the server to cancel the running statement (best effort).
+
+        Call from another thread; the interrupted call raises OperationalError.
+        """
         ...
 
     def close(self) -> None:
-        """Close the database connection."""
+        """Close the database connection and its socket; a second call does nothing."""
         ...
 
     def is_closed(self) -> bool:
         """Check if the connection is closed."""
         ...
 
+    def info(self) -> Dict[str, Any]:
+        """closed, healthy, cached_statements, and last_error: why the connection dropped, or None."""
+        ...
+
+    @property
+    def last_used(self) -> float:
+        """When the connection last ran a statement, as a ``time.time()`` value."""
+        ...
+
+    def idle_seconds(self) -> float:
+        """Seconds since the connection last ran a statement."""
+        ...
+
+    def transaction(self) -> Transaction:
+        """Begin a new transaction; as a context manager it commits on success.
+
+        Statements run through the transaction bypass the prepared statement
+        cache for now.
+        """
+        ...
+
     def begin(self) -> Transaction:
-        """Begin a new transaction."""
+        """Begin a new transaction (alias of transaction)."""
+        ...
+
+    autocommit: bool
+    """Whether each statement commits on its own; assigning it calls ``set_autocommit``."""
+
+    paramstyle: Literal["numeric", "pyformat"]
+    """How this connection's queries write placeholders."""
+
+    def set_autocommit(self, autocommit: bool) -> None:
+        """Turn autocommit on or off; raises ProgrammingError mid-transaction.
+
+        With autocommit off the first statement sends BEGIN and later ones
+        join that transaction until ``commit()`` or ``rollback()``.
+        """
+        ...
+
+    def commit(self) -> None:
+        """Commit the transaction opened with autocommit off; no-op otherwise."""
+        ...
+
+    def rollback(self) -> None:
+        """Roll back the transaction opened with autocommit off; no-op otherwise."""
+        ...
+
+    def query_stream(
+        self, query: str, params: Optional[Params] = None, batch_size: int = 1000
+    ) -> Cursor:
+        """Iterate over the rows of a query, fetched and converted ``batch_size`` at a time."""
+        ...
+
+    def cursor(
+        self,
+        query: str,
+        params: Optional[Params] = None,
+        fetch_size: int = 1000,
+        scroll: bool = False,
+    ) -> Cursor:
+        """Open a server-side cursor that fetches rows ``fetch_size`` at a time.
+
+        Outside a transaction the cursor opens its own and commits it on close.
+        ``scroll=True`` enables ``fetch_backward`` and ``fetch_absolute``.
+        """
+        ...
+
+    def declare_cursor(
+        self, name: str, query: str, params: Optional[Params] = None, scroll: bool = False
+    ) -> None:
+        """Declare a named cursor in the open transaction for manual FETCH control."""
+        ...
+
+    def fetch_cursor(self, name: str, count: int = 1, direction: str = "forward") -> List[Row]:
+        """FETCH from a declared cursor; direction is forward, backward, absolute or relative."""
+        ...
+
+    def close_cursor(self, name: str) -> None:
+        """Close a cursor declared with declare_cursor."""
         ...
 
     def __enter__(self) -> 'Connection':
-        """Context manager entry."""
+        """Context manager entry - returns this same connection object."""
         ...
 
-    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
-        """Context manager exit."""
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> bool:
+        """Context manager exit - closes the connection when the outermost block exits."""
         ...
 
-def connect(connection_string: str) -> Connection:
+def connect(connection_string: str, **kwargs: Any) -> Connection:
     """Connect to a PostgreSQL database."""
     ...
 
 def get_version() -> str:
     """Get the driver version."""
-    ...
\ No newline at end of file
+    ...
+def quote_ident(name: str) -> str:
+    """Quote an SQL identifier like the server's quote_ident ("users" -> users, "My Table" -> "My Table")."""
+    ...
+
+def quote_literal(value: Any) -> str:
+    """Quote a value as an SQL literal like the server's quote_literal; None gives NULL."""
+    ...
@@ -0,0 +1,167 @@
+use std::ptr::addr_of_mut;
+use std::sync::Arc;
+
+use arrow_array::ffi::to_ffi;
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, Date32Array, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    RecordBatch, RecordBatchOptions, StringArray, StructArray, TimestampMicrosecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use postgres_types::{FromSql, Type};
+use pyo3::types::PyList;
+use pyo3::{PyAny, PyObject, PyResult, Python};
+use tokio_postgres::{Column, Row};
+
+use crate::error::{not_supported_error, DataError, InternalError};
+use crate::types::ConversionOptions;
+
+/// Arrow type a PostgreSQL column is decoded to, or None if it has none
+///
+/// Integers and floats keep their width, timestamps are microseconds (UTC
+/// for TIMESTAMPTZ) and every type that decodes as a string becomes utf8.
+fn data_type(ty: &Type) -> Option<DataType> {
+    match *ty {
+        Type::BOOL => Some(DataType::Boolean),
+        Type::INT2 => Some(DataType::Int16),
+        Type::INT4 => Some(DataType::Int32),
+        Type::INT8 => Some(DataType::Int64),
+        Type::FLOAT4 => Some(DataType::Float32),
+        Type::FLOAT8 => Some(DataType::Float64),
+        Type::DATE => Some(DataType::Date32),
+        Type::TIMESTAMP => Some(DataType::Timestamp(TimeUnit::Microsecond, None)),
+        Type::TIMESTAMPTZ => Some(DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))),
+        _ if <&str as FromSql>::accepts(ty) => Some(DataType::Utf8),
+        _ => None,
+    }
+}
+
+/// Build a single record batch from a result, one Arrow array per column
+///
+/// Raises NotSupportedError naming the first column without an Arrow type,
+/// before anything is decoded.
+pub fn record_batch(columns: &[Column], rows: &[Row], options: ConversionOptions) -> PyResult<RecordBatch> {
+    let mut fields = Vec::with_capacity(columns.len());
+    for column in columns {
+        let data_type = data_type(column.type_()).ok_or_else(|| {
+            not_supported_error(&format!(
+                "Arrow output for column \"{}\" of type {}; cast it in the query, e.g. to text",
+                column.name(),
+                column.type_()
+            ))
+        })?;
+        fields.push(Field::new(column.name(), data_type, true));
+    }
+
+    let mut arrays = Vec::with_capacity(columns.len());
+    for (idx, field) in fields.iter().enumerate() {
+        arrays.push(column_array(rows, idx, field.data_type(), options)?);
+    }
+
+    // The row count is given for results without columns
+    let batch_options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &batch_options)
+        .map_err(|e| InternalError::new_err(e.to_string()))
+}
+
+/// Hand a record batch to pyarrow through the Arrow C Data Interface and
+/// return it as a `pyarrow.Table`
+///
+/// pyarrow takes over the buffers, so nothing is copied.
+pub fn to_pyarrow(py: Python, pyarrow: &PyAny, batch: RecordBatch) -> PyResult<PyObject> {
+    let data = StructArray::from(batch).into_data();
+    let (mut array, mut schema) = to_ffi(&data).map_err(|e| InternalError::new_err(e.to_string()))?;
+
+    // Importing moves out of both structs, leaving nothing for their Drop
+    let batch = pyarrow.getattr("RecordBatch")?.call_method1(
+        "_import_from_c",
+        (addr_of_mut!(array) as usize, addr_of_mut!(schema) as usize),
+    )?;
+    let table = pyarrow.getattr("Table")?.call_method1("from_batches", (PyList::new(py, [batch]),))?;
+    Ok(table.into())
+}
+
+/// Decode one column into an array of `data_type`
+fn column_array(rows: &[Row], idx: usize, data_type: &DataType, options: ConversionOptions) -> PyResult<ArrayRef> {
+    let array: ArrayRef = match data_type {
+        DataType::Boolean => Arc::new(BooleanArray::from(column_values::<bool>(rows, idx, options)?)),
+        DataType::Int16 => Arc::new(Int16Array::from(column_values::<i16>(rows, idx, options)?)),
+        DataType::Int32 => Arc::new(Int32Array::from(column_values::<i32>(rows, idx, options)?)),
+        DataType::Int64 => Arc::new(Int64Array::from(column_values::<i64>(rows, idx, options)?)),
+        DataType::Float32 => Arc::new(Float32Array::from(column_values::<f32>(rows, idx, options)?)),
+        DataType::Float64 => Arc::new(Float64Array::from(column_values::<f64>(rows, idx, options)?)),
+        DataType::Date32 => {
+            let values = column_values::<NaiveDate>(rows, idx, options)?;
+            Arc::new(Date32Array::from_iter(values.into_iter().map(|d| d.map(epoch_days))))
+        }
+        DataType::Timestamp(_, None) => {
+            let values = column_values::<NaiveDateTime>(rows, idx, options)?;
+            Arc::new(TimestampMicrosecondArray::from_iter(
+                values.into_iter().map(|ts| ts.map(|ts| ts.and_utc().timestamp_micros())),
+            ))
+        }
+        DataType::Timestamp(_, Some(zone)) => {
+            let values = column_values::<DateTime<Utc>>(rows, idx, options)?;
+            Arc::new(
+                TimestampMicrosecondArray::from_iter(values.into_iter().map(|ts| ts.map(|ts| ts.timestamp_micros())))
+                    .with_timezone(zone.clone()),
+            )
+        }
+        _ => Arc::new(StringArray::from(column_values::<&str>(rows, idx, options)?)),
+    };
+    debug_assert_eq!(array.data_type(), data_type);
+    Ok(array)
+}
+
+/// Every value of a column; ones that can't be decoded (such as infinite
+/// timestamps) are NULL, or DataError in strict mode
+fn column_values<'a, T: FromSql<'a>>(
+    rows: &'a [Row],
+    idx: usize,
+    options: ConversionOptions,
+) -> PyResult<Vec<Option<T>>> {
+    rows.iter()
+        .map(|row| match row.try_get::<_, Option<T>>(idx) {
+            Ok(value) => Ok(value),
+            Err(e) if options.strict => Err(DataError::new_err(e.to_string())),
+            Err(_) => Ok(None),
+        })
+        .collect()
+}
+
+/// Days since 1970-01-01, as Arrow's date32 stores them
+fn epoch_days(date: NaiveDate) -> i32 {
+    date.signed_duration_since(DateTime::UNIX_EPOCH.date_naive()).num_days() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_type() {
+        assert_eq!(data_type(&Type::INT2), Some(DataType::Int16));
+        assert_eq!(data_type(&Type::INT8), Some(DataType::Int64));
+        assert_eq!(data_type(&Type::FLOAT8), Some(DataType::Float64));
+        assert_eq!(data_type(&Type::BOOL), Some(DataType::Boolean));
+        assert_eq!(data_type(&Type::TEXT), Some(DataType::Utf8));
+        assert_eq!(data_type(&Type::VARCHAR), Some(DataType::Utf8));
+        assert_eq!(data_type(&Type::NAME), Some(DataType::Utf8));
+        assert_eq!(data_type(&Type::TIMESTAMP), Some(DataType::Timestamp(TimeUnit::Microsecond, None)));
+        assert_eq!(
+            data_type(&Type::TIMESTAMPTZ),
+            Some(DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())))
+        );
+
+        assert_eq!(data_type(&Type::NUMERIC), None);
+        assert_eq!(data_type(&Type::JSONB), None);
+        assert_eq!(data_type(&Type::INT4_ARRAY), None);
+    }
+
+    #[test]
+    fn test_epoch_days() {
+        assert_eq!(epoch_days(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()), 0);
+        assert_eq!(epoch_days(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()), 10957);
+        assert_eq!(epoch_days(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()), -1);
+    }
+}
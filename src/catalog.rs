@@ -0,0 +1,119 @@
+/// Columns of a table, in definition order
+///
+/// `information_schema` identifiers are domains over `name`, so they are
+/// cast to text for decoding.
+pub const TABLE_COLUMNS_SQL: &str = "\
+    SELECT column_name::text, data_type::text, is_nullable::text = 'YES', column_default::text \
+    FROM information_schema.columns \
+    WHERE table_schema = $1 AND table_name = $2 \
+    ORDER BY ordinal_position";
+
+/// Fields of a composite type, in attribute order
+///
+/// The name is resolved with `regtype`, so it follows `search_path` and may
+/// be schema-qualified or quoted. No rows means the type isn't composite.
+pub const COMPOSITE_FIELDS_SQL: &str = "\
+    SELECT t.oid, n.nspname::text, t.typname::text, a.attname::text \
+    FROM pg_type t \
+    JOIN pg_namespace n ON n.oid = t.typnamespace \
+    JOIN pg_attribute a ON a.attrelid = t.typrelid \
+    WHERE t.oid = $1::text::regtype AND t.typtype = 'c' AND a.attnum > 0 AND NOT a.attisdropped \
+    ORDER BY a.attnum";
+
+/// Whether pg_stat_statements can be queried: the view is on `search_path`
+/// and the library is preloaded (reading the view fails otherwise)
+pub const STAT_STATEMENTS_AVAILABLE_SQL: &str = "\
+    SELECT to_regclass('pg_stat_statements') IS NOT NULL \
+    AND 'pg_stat_statements' = ANY(string_to_array(replace(current_setting('shared_preload_libraries'), ' ', ''), ','))";
+
+/// The pg_stat_statements entry of the current user and database for a
+/// statement, by query identifier ($1) or, failing that, by text ($2)
+///
+/// PostgreSQL 14+ keeps separate top-level and nested entries; the one with
+/// the most calls is returned.
+pub const QUERY_STATS_SQL: &str = "\
+    SELECT s.* FROM pg_stat_statements s \
+    WHERE s.dbid = (SELECT oid FROM pg_database WHERE datname = current_database()) \
+    AND s.userid = (SELECT oid FROM pg_roles WHERE rolname = current_user) \
+    AND (s.queryid = $1 OR ($1 IS NULL AND btrim(s.query, E' \\t\\r\\n;') = $2)) \
+    ORDER BY s.calls DESC \
+    LIMIT 1";
+
+/// Split a possibly schema-qualified name into its identifiers
+///
+/// Follows SQL rules: unquoted parts are folded to lower case, double-quoted
+/// parts are taken literally (with `""` standing for a quote), and a dot
+/// only separates parts outside quotes. Returns None for anything that
+/// isn't one or two well-formed identifiers.
+pub fn split_qualified_name(name: &str) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut chars = name.trim().chars().peekable();
+
+    loop {
+        let mut part = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        part.push('"');
+                    }
+                    '"' => break,
+                    c => part.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == '.' || c == '"' {
+                    break;
+                }
+                part.extend(c.to_lowercase());
+                chars.next();
+            }
+            part = part.trim().to_string();
+        }
+
+        if part.is_empty() {
+            return None;
+        }
+        parts.push(part);
+
+        match chars.next() {
+            None => break,
+            Some('.') => continue,
+            Some(_) => return None,
+        }
+    }
+
+    if parts.len() > 2 {
+        return None;
+    }
+    Some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(name: &str) -> Option<Vec<String>> {
+        split_qualified_name(name)
+    }
+
+    #[test]
+    fn test_split_qualified_name() {
+        assert_eq!(split("Users"), Some(vec!["users".to_string()]));
+        assert_eq!(split("Sales.Orders"), Some(vec!["sales".to_string(), "orders".to_string()]));
+        assert_eq!(
+            split("\"My Schema\".\"Odd.\"\"Name\""),
+            Some(vec!["My Schema".to_string(), "Odd.\"Name".to_string()])
+        );
+        assert_eq!(split("public.\"Users\""), Some(vec!["public".to_string(), "Users".to_string()]));
+
+        assert_eq!(split(""), None);
+        assert_eq!(split("a..b"), None);
+        assert_eq!(split("a.b.c"), None);
+        assert_eq!(split("\"unterminated"), None);
+        assert_eq!(split("\"a\"b"), None);
+    }
+}
@@ -0,0 +1,160 @@
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use postgres_types::{FromSql, Kind, Type};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::types::{decode_failure, field_to_py, ConversionOptions};
+
+/// Layout of a registered composite type and the Python class it decodes to
+pub struct CompositeCodec {
+    schema: String,
+    name: String,
+    fields: Vec<String>,
+    // A namedtuple class; None decodes to dicts
+    record: Option<PyObject>,
+}
+
+impl CompositeCodec {
+    pub fn new(schema: String, name: String, fields: Vec<String>, record: Option<PyObject>) -> Self {
+        Self { schema, name, fields, record }
+    }
+}
+
+// Registered composite types by OID. OIDs are only unique within a
+// database, so the schema and name are checked again on every lookup.
+static REGISTRY: Lazy<RwLock<HashMap<u32, Arc<CompositeCodec>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Register (or replace) the codec for the composite type `oid`
+pub fn register(oid: u32, codec: CompositeCodec) {
+    REGISTRY.write().insert(oid, Arc::new(codec));
+}
+
+fn lookup(ty: &Type) -> Option<Arc<CompositeCodec>> {
+    let codec = REGISTRY.read().get(&ty.oid())?.clone();
+    (codec.schema == ty.schema() && codec.name == ty.name()).then_some(codec)
+}
+
+/// Whether values of `ty` decode through a registered composite codec
+pub fn is_registered(ty: &Type) -> bool {
+    matches!(ty.kind(), Kind::Composite(_)) && lookup(ty).is_some()
+}
+
+/// Raw fields of a composite value, in attribute order
+pub struct CompositeFields<'a>(pub Vec<Option<&'a [u8]>>);
+
+impl<'a> FromSql<'a> for CompositeFields<'a> {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Binary format: field count, then per field its type OID, length
+        // (-1 for NULL) and value
+        fn take<'a>(raw: &mut &'a [u8], len: usize) -> Result<&'a [u8], Box<dyn std::error::Error + Sync + Send>> {
+            if raw.len() < len {
+                return Err("invalid composite value".into());
+            }
+            let (head, rest) = raw.split_at(len);
+            *raw = rest;
+            Ok(head)
+        }
+        let int = |bytes: &[u8]| i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+        let mut raw = raw;
+        let count = int(take(&mut raw, 4)?);
+        let mut fields = Vec::with_capacity(count.max(0) as usize);
+        for _ in 0..count {
+            take(&mut raw, 4)?;
+            let len = int(take(&mut raw, 4)?);
+            fields.push(if len < 0 { None } else { Some(take(&mut raw, len as usize)?) });
+        }
+        Ok(CompositeFields(fields))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Composite(_))
+    }
+}
+
+/// Decode a column of a registered composite type
+pub fn composite_to_py(
+    py: Python,
+    row: &tokio_postgres::Row,
+    idx: usize,
+    ty: &Type,
+    options: ConversionOptions,
+) -> PyResult<PyObject> {
+    match row.try_get::<_, Option<CompositeFields>>(idx) {
+        Ok(Some(fields)) => fields_to_py(py, ty, fields, options),
+        Ok(None) => Ok(py.None()),
+        Err(e) => decode_failure(py, options, e),
+    }
+}
+
+/// Decode a nested value of a registered composite type from its binary form
+pub fn composite_value_to_py(
+    py: Python,
+    ty: &Type,
+    raw: &[u8],
+    options: ConversionOptions,
+) -> Result<PyResult<PyObject>, Box<dyn std::error::Error + Sync + Send>> {
+    let fields = CompositeFields::from_sql(ty, raw)?;
+    Ok(fields_to_py(py, ty, fields, options))
+}
+
+fn fields_to_py(py: Python, ty: &Type, fields: CompositeFields, options: ConversionOptions) -> PyResult<PyObject> {
+    let codec = lookup(ty).ok_or_else(|| crate::error::type_conversion_error("registered composite type", ty.name()))?;
+    let field_types = match ty.kind() {
+        Kind::Composite(field_types) => field_types,
+        _ => return Err(crate::error::type_conversion_error("composite type", ty.name())),
+    };
+    if field_types.len() != codec.fields.len() || fields.0.len() != codec.fields.len() {
+        return Err(crate::error::DataError::new_err(format!(
+            "Composite type {} has changed since it was registered; register it again",
+            ty.name()
+        )));
+    }
+
+    let values = field_types
+        .iter()
+        .zip(fields.0)
+        .map(|(field, raw)| field_to_py(py, field.type_(), raw, options))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    match &codec.record {
+        Some(record) => Ok(record.call1(py, PyTuple::new(py, values))?),
+        None => {
+            let dict = PyDict::new(py);
+            for (name, value) in codec.fields.iter().zip(values) {
+                dict.set_item(name, value)?;
+            }
+            Ok(dict.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_fields() {
+        let ty = Type::new(
+            "pair".to_string(),
+            16_400,
+            Kind::Composite(vec![]),
+            "public".to_string(),
+        );
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&2i32.to_be_bytes());
+        raw.extend_from_slice(&Type::INT4.oid().to_be_bytes());
+        raw.extend_from_slice(&4i32.to_be_bytes());
+        raw.extend_from_slice(&7i32.to_be_bytes());
+        raw.extend_from_slice(&Type::TEXT.oid().to_be_bytes());
+        raw.extend_from_slice(&(-1i32).to_be_bytes());
+
+        let fields = CompositeFields::from_sql(&ty, &raw).unwrap();
+        assert_eq!(fields.0, vec![Some(&7i32.to_be_bytes()[..]), None]);
+
+        assert!(CompositeFields::from_sql(&ty, &raw[..10]).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+use bytes::Bytes;
+use futures_util::{SinkExt, StreamExt};
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyString};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_postgres::binary_copy::BinaryCopyInWriter;
+use tokio_postgres::types::Type;
+use tokio_postgres::{Client, CopyOutStream};
+
+use crate::connection::StatementDeadline;
+use crate::error::{map_db_error, type_conversion_error, DataError};
+use crate::quote::{object_ident, quote_ident};
+use crate::runtime::RuntimeManager;
+use crate::types::{py_objects_to_postgres_values, ConversionOptions};
+
+/// Run `COPY ... FROM STDIN` for either raw CSV data or an iterable of tuples
+///
+/// `bytes`, `bytearray` and `str` payloads are sent as-is in CSV format.
+/// Any other object is treated as an iterable of row sequences, converted
+/// with the parameter binding machinery and written in binary COPY format.
+/// Waits on the server run with the GIL released and within `deadline`.
+#[allow(clippy::too_many_arguments)]
+pub fn copy_in(
+    py: Python,
+    client: &Arc<Mutex<Client>>,
+    runtime: &RuntimeManager,
+    deadline: &StatementDeadline,
+    table: &str,
+    data: &PyAny,
+    columns: Option<&[String]>,
+    options: ConversionOptions,
+) -> PyResult<u64> {
+    let target = CopyTarget::new(table, columns)?;
+
+    let payload = if let Ok(b) = data.downcast::<PyBytes>() {
+        Some(b.as_bytes().to_vec())
+    } else if let Ok(b) = data.downcast::<PyByteArray>() {
+        Some(b.to_vec())
+    } else if let Ok(s) = data.downcast::<PyString>() {
+        Some(s.to_str()?.as_bytes().to_vec())
+    } else {
+        None
+    };
+
+    match payload {
+        Some(payload) => copy_in_csv(py, client, runtime, deadline, &target.to_string(), payload),
+        None => copy_in_rows(py, client, runtime, deadline, &target, data, options),
+    }
+}
+
+/// Run `COPY ... FROM STDIN` for an iterable of row sequences
+///
+/// Like `copy_in`, without accepting raw CSV payloads: `str` and `bytes`
+/// would otherwise be iterated as rows one character or byte wide.
+#[allow(clippy::too_many_arguments)]
+pub fn copy_rows(
+    py: Python,
+    client: &Arc<Mutex<Client>>,
+    runtime: &RuntimeManager,
+    deadline: &StatementDeadline,
+    table: &str,
+    rows: &PyAny,
+    columns: Option<&[String]>,
+    options: ConversionOptions,
+) -> PyResult<u64> {
+    if rows.is_instance_of::<PyBytes>() || rows.is_instance_of::<PyByteArray>() || rows.is_instance_of::<PyString>() {
+        return Err(type_conversion_error("iterable of row sequences (use copy_in for CSV data)", "str or bytes"));
+    }
+    let target = CopyTarget::new(table, columns)?;
+    copy_in_rows(py, client, runtime, deadline, &target, rows, options)
+}
+
+/// Stream a CSV payload into the target table
+fn copy_in_csv(
+    py: Python,
+    client: &Arc<Mutex<Client>>,
+    runtime: &RuntimeManager,
+    deadline: &StatementDeadline,
+    target: &str,
+    payload: Vec<u8>,
+) -> PyResult<u64> {
+    let client = Arc::clone(client);
+    let sql = format!("COPY {} FROM STDIN (FORMAT csv)", target);
+
+    deadline.block_on(py, runtime, async move {
+        let client = client.lock().await;
+        let sink = client.copy_in::<_, Bytes>(&sql).await.map_err(map_db_error)?;
+        tokio::pin!(sink);
+
+        sink.send(Bytes::from(payload)).await.map_err(map_db_error)?;
+        sink.as_mut().finish().await.map_err(map_db_error)
+    })
+}
+
+/// Convert each Python row and write it using the binary COPY protocol
+///
+/// The client lock is held for the whole COPY so no other statement can be
+/// interleaved on this connection. Rows are converted under the GIL in
+/// batches of `COPY_BATCH`, and each batch written with it released. If a
+/// row fails to convert or the deadline passes, the writer is dropped,
+/// which aborts the COPY and leaves the table unchanged.
+#[allow(clippy::too_many_arguments)]
+fn copy_in_rows(
+    py: Python,
+    client: &Arc<Mutex<Client>>,
+    runtime: &RuntimeManager,
+    deadline: &StatementDeadline,
+    target: &CopyTarget,
+    rows: &PyAny,
+    options: ConversionOptions,
+) -> PyResult<u64> {
+    let client = deadline.block_on(py, runtime, async { Ok(Arc::clone(client).lock_owned().await) })?;
+
+    // Resolve the column types the binary format requires
+    let column_list = target.columns.as_deref().unwrap_or("*");
+    let describe = format!("SELECT {} FROM {} LIMIT 0", column_list, target.table);
+    let types: Vec<Type> = deadline.block_on(py, runtime, async {
+        let statement = client.prepare(&describe).await.map_err(map_db_error)?;
+        Ok(statement.columns().iter().map(|c| c.type_().clone()).collect())
+    })?;
+
+    let sql = format!("COPY {} FROM STDIN (FORMAT binary)", target);
+    let sink = deadline.block_on(py, runtime, async { client.copy_in(&sql).await.map_err(map_db_error) })?;
+    let mut writer = Box::pin(BinaryCopyInWriter::new(sink, &types));
+
+    let mut batch = Vec::with_capacity(COPY_BATCH);
+    for (row_index, row) in rows.iter()?.enumerate() {
+        let values = row?
+            .iter()?
+            .map(|value| value.map(|v| v.into()))
+            .collect::<PyResult<Vec<PyObject>>>()
+            .map_err(|e| row_error(py, row_index, e))?;
+
+        if values.len() != types.len() {
+            return Err(DataError::new_err(format!(
+                "Row {}: expected {} values, got {}",
+                row_index,
+                types.len(),
+                values.len()
+            )));
+        }
+
+        let params = py_objects_to_postgres_values(py, &values, options)
+            .map_err(|e| row_error(py, row_index, e))?;
+        batch.push((row_index, params));
+        if batch.len() == COPY_BATCH {
+            write_rows(py, runtime, deadline, writer.as_mut(), &mut batch)?;
+        }
+    }
+    write_rows(py, runtime, deadline, writer.as_mut(), &mut batch)?;
+
+    deadline.block_on(py, runtime, async { writer.as_mut().finish().await.map_err(map_db_error) })
+}
+
+// Rows converted under the GIL before they are written with it released
+const COPY_BATCH: usize = 1000;
+
+type CopyRow = (usize, Vec<Box<dyn postgres_types::ToSql + Sync + Send>>);
+
+/// Write a batch of converted rows with the GIL released, emptying it
+fn write_rows(
+    py: Python,
+    runtime: &RuntimeManager,
+    deadline: &StatementDeadline,
+    mut writer: Pin<&mut BinaryCopyInWriter>,
+    batch: &mut Vec<CopyRow>,
+) -> PyResult<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    deadline.block_on(py, runtime, async {
+        for (row_index, params) in batch.iter() {
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+            writer
+                .as_mut()
+                .write(&params_refs)
+                .await
+                .map_err(|e| DataError::new_err(format!("Row {}: {}", row_index, e)))?;
+        }
+        Ok(())
+    })?;
+    batch.clear();
+    Ok(())
+}
+
+// The server sends COPY data one row per message; rows are merged into
+// chunks of about this size before reaching Python
+const COPY_OUT_CHUNK: usize = 64 * 1024;
+
+/// Build the `COPY ... TO STDOUT` statement for a table or query
+///
+/// `source` is taken as a table name when it is a (schema-qualified)
+/// identifier, and as a query otherwise.
+pub fn copy_out_sql(source: &str, format: &str, header: bool) -> PyResult<String> {
+    let format = match format.to_lowercase().as_str() {
+        "csv" => "csv",
+        "binary" => "binary",
+        "text" => "text",
+        _ => return Err(type_conversion_error("'csv', 'binary' or 'text'", format)),
+    };
+    if header && format != "csv" {
+        return Err(pyo3::exceptions::PyValueError::new_err("header is only available with format='csv'"));
+    }
+
+    Ok(copy_out_statement(source, format, header))
+}
+
+fn copy_out_statement(source: &str, format: &str, header: bool) -> String {
+    let source = source.trim().trim_end_matches(';');
+    let target = if !source.is_empty() && source.split('.').all(is_identifier) {
+        qualified_ident(source).expect("identifiers are non-empty without NULs")
+    } else {
+        format!("({})", source)
+    };
+    let header = if header { ", HEADER" } else { "" };
+    format!("COPY {} TO STDOUT (FORMAT {}{})", target, format, header)
+}
+
+/// Start a `COPY ... TO STDOUT`
+pub fn copy_out(client: &Arc<Mutex<Client>>, runtime: &RuntimeManager, sql: String) -> PyResult<CopyOutIterator> {
+    let client = Arc::clone(client);
+    let stream = runtime.block_on(async move {
+        let client = client.lock().await;
+        client.copy_out(sql.as_str()).await.map_err(map_db_error)
+    })?;
+
+    Ok(CopyOutIterator {
+        stream: Some(Box::pin(stream)),
+        runtime: runtime.clone(),
+    })
+}
+
+/// Iterator over the output of `COPY ... TO STDOUT`, in chunks of bytes
+///
+/// Chunks are read from the server as the iterator advances, so memory use
+/// stays at about one chunk however large the export. Until the iterator is
+/// exhausted or closed the connection is busy with the COPY and can't run
+/// other statements.
+#[pyclass(name = "CopyOutIterator")]
+pub struct CopyOutIterator {
+    stream: Option<Pin<Box<CopyOutStream>>>,
+    runtime: RuntimeManager,
+}
+
+#[pymethods]
+impl CopyOutIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        Ok(self.next_chunk(py)?.map(|chunk| PyBytes::new(py, &chunk).into()))
+    }
+
+    /// Stop the export early, discarding the rest of the output
+    ///
+    /// The server still finishes the COPY; its remaining output is read
+    /// and dropped by the connection's background task up to the final
+    /// ReadyForQuery, so the next statement on the connection starts from a
+    /// clean protocol state rather than reading leftover COPY data.
+    pub fn close(&mut self) {
+        self.stream = None;
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(&mut self, _exc_type: Option<PyObject>, _exc_val: Option<PyObject>, _exc_tb: Option<PyObject>) -> bool {
+        self.close();
+        false
+    }
+}
+
+impl CopyOutIterator {
+    /// Read the next chunk with the GIL released; None once the COPY is done
+    pub fn next_chunk(&mut self, py: Python) -> PyResult<Option<Vec<u8>>> {
+        let stream = match self.stream.as_mut() {
+            Some(stream) => stream,
+            None => return Ok(None),
+        };
+
+        let runtime = &self.runtime;
+        let (chunk, done) = py.allow_threads(|| {
+            runtime.block_on(async {
+                let mut chunk = Vec::new();
+                while chunk.len() < COPY_OUT_CHUNK {
+                    match stream.next().await {
+                        Some(Ok(data)) => chunk.extend_from_slice(&data),
+                        Some(Err(e)) => return Err(map_db_error(e)),
+                        None => return Ok((chunk, true)),
+                    }
+                }
+                Ok((chunk, false))
+            })
+        })
+        .inspect_err(|_| self.stream = None)?;
+
+        if done {
+            self.stream = None;
+        }
+        Ok(if chunk.is_empty() { None } else { Some(chunk) })
+    }
+}
+
+/// The `table (col, ...)` target of a COPY statement, with every name quoted
+///
+/// Names are case-sensitive, as written; a dot separates the schema from
+/// the table name.
+struct CopyTarget {
+    table: String,
+    columns: Option<String>,
+}
+
+impl CopyTarget {
+    fn new(table: &str, columns: Option<&[String]>) -> PyResult<Self> {
+        let table = qualified_ident(table).ok_or_else(|| type_conversion_error("valid SQL identifier", table))?;
+        let columns = match columns {
+            Some(cols) if !cols.is_empty() => Some(
+                cols.iter().map(|c| object_ident(c)).collect::<PyResult<Vec<_>>>()?.join(", "),
+            ),
+            _ => None,
+        };
+        Ok(CopyTarget { table, columns })
+    }
+}
+
+impl std::fmt::Display for CopyTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.columns {
+            Some(columns) => write!(f, "{} ({})", self.table, columns),
+            None => f.write_str(&self.table),
+        }
+    }
+}
+
+/// Quote each dot-separated part of an optionally schema-qualified name
+fn qualified_ident(name: &str) -> Option<String> {
+    name.split('.').map(quote_ident).collect::<Option<Vec<_>>>().map(|parts| parts.join("."))
+}
+
+/// Whether a COPY TO source reads as a table name rather than a query
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Prefix a conversion error with the index of the offending row
+fn row_error(py: Python, row_index: usize, error: PyErr) -> PyErr {
+    DataError::new_err(format!("Row {}: {}", row_index, error.value(py)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_target_quotes_names() {
+        let columns = ["id".to_string(), "Full Name".to_string()];
+        let target = CopyTarget::new("public.Users", Some(&columns)).unwrap();
+        assert_eq!(target.to_string(), "public.\"Users\" (id, \"Full Name\")");
+        assert_eq!(CopyTarget::new("events", None).unwrap().to_string(), "events");
+        assert_eq!(CopyTarget::new("order", Some(&[])).unwrap().to_string(), "\"order\"");
+        for table in ["", "public.", "a\0b"] {
+            assert!(CopyTarget::new(table, None).is_err());
+        }
+    }
+
+    #[test]
+    fn test_copy_out_statement() {
+        assert_eq!(copy_out_statement("public.users", "csv", true), "COPY public.users TO STDOUT (FORMAT csv, HEADER)");
+        assert_eq!(copy_out_statement("Users", "csv", false), "COPY \"Users\" TO STDOUT (FORMAT csv)");
+        assert_eq!(
+            copy_out_statement("SELECT id FROM users WHERE active;", "binary", false),
+            "COPY (SELECT id FROM users WHERE active) TO STDOUT (FORMAT binary)"
+        );
+        assert_eq!(copy_out_statement("events", "text", false), "COPY events TO STDOUT (FORMAT text)");
+    }
+}
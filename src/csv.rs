@@ -0,0 +1,43 @@
+/// Append one CSV record (RFC 4180) to `out`, terminated by CRLF
+///
+/// `None` fields are written empty while empty strings are written as `""`,
+/// so NULL and '' stay distinguishable, as in PostgreSQL's CSV COPY format.
+pub fn write_record<'a>(out: &mut String, fields: impl IntoIterator<Item = Option<&'a str>>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match field {
+            None => {}
+            Some(field) => write_field(out, field),
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// Append a single field, quoted when it contains a separator, quote or
+/// line break, or is empty
+fn write_field(out: &mut String, field: &str) {
+    if field.is_empty() || field.contains([',', '"', '\r', '\n']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_record_quoting() {
+        let mut out = String::new();
+        write_record(&mut out, [Some("id"), Some("note")]);
+        write_record(&mut out, [Some("1"), Some("say \"hi\", then\nleave")]);
+        write_record(&mut out, [Some("2"), None]);
+        write_record(&mut out, [Some("3"), Some("")]);
+        assert_eq!(out, "id,note\r\n1,\"say \"\"hi\"\", then\nleave\"\r\n2,\r\n3,\"\"\r\n");
+    }
+}
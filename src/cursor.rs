@@ -0,0 +1,332 @@
+use pyo3::prelude::*;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::{Client, Row as TokioRow};
+
+use crate::error::{map_db_error, not_supported_error};
+use crate::row::Row;
+use crate::runtime::RuntimeManager;
+use crate::state::ConnectionState;
+use crate::types::ConversionOptions;
+
+// Numbers the cursors of this process so their names never clash
+static CURSOR_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Server-side cursor that fetches a large result in batches
+///
+/// Created by `Connection.cursor()`: the query runs as a `DECLARE ... CURSOR`
+/// and rows are pulled with `FETCH`, so only one batch is held in memory at
+/// a time. Cursors live inside a transaction; when the connection isn't in
+/// one, the cursor opens its own and commits it on close. The cursor closes
+/// on `__exit__`, on `close()`, once all rows have been fetched, or when it
+/// is garbage collected.
+///
+/// Cursors opened with `scroll=True` can also move backward with
+/// `fetch_backward(n)` and jump with `fetch_absolute(n)`. They stay open
+/// after reaching the end, until closed.
+#[pyclass(name = "Cursor")]
+pub struct Cursor {
+    client: Arc<Mutex<Client>>,
+    runtime: RuntimeManager,
+    conn_state: Arc<ConnectionState>,
+    conversion: ConversionOptions,
+    /// Server-side name of the cursor
+    #[pyo3(get)]
+    name: String,
+    /// Rows requested per batch while iterating
+    #[pyo3(get)]
+    fetch_size: u64,
+    /// Declared SCROLL, so it can move backward
+    #[pyo3(get)]
+    scroll: bool,
+    // Rows fetched ahead of the caller; the server position is past them
+    rows: VecDeque<TokioRow>,
+    // The server has no more rows
+    exhausted: bool,
+    // The server position is after the last row, one past the last row the
+    // caller got (scroll cursors only)
+    past_end: bool,
+    // The caller is past the last row as well, after fetch_absolute beyond it
+    beyond_result: bool,
+    closed: bool,
+    // The cursor started the transaction it lives in
+    owns_transaction: bool,
+}
+
+#[pymethods]
+impl Cursor {
+    /// Fetch the next rows
+    ///
+    /// Args:
+    ///     size: Number of rows (optional, defaults to fetch_size)
+    ///
+    /// Returns:
+    ///     list: Up to `size` Row objects; empty once all rows are fetched
+    #[pyo3(signature = (size=None))]
+    pub fn fetchmany(&mut self, py: Python, size: Option<u64>) -> PyResult<Vec<Row>> {
+        let size = size.unwrap_or(self.fetch_size);
+        let buffered = (size as usize).min(self.rows.len());
+        let mut rows: Vec<TokioRow> = self.rows.drain(..buffered).collect();
+        if (rows.len() as u64) < size {
+            rows.extend(self.fetch(py, size - rows.len() as u64)?);
+        }
+        rows.iter().map(|row| Row::from_tokio_row(py, row, self.conversion)).collect()
+    }
+
+    /// Fetch the next row, or None once all rows are fetched
+    pub fn fetchone(&mut self, py: Python) -> PyResult<Option<Row>> {
+        self.next_row(py)
+    }
+
+    /// Fetch the rows before the current position (scroll cursors only)
+    ///
+    /// Rows come back in backward order, nearest first, and the position
+    /// moves to the last of them; iterating or `fetchmany` then continues
+    /// forward from there.
+    ///
+    /// Args:
+    ///     count: Number of rows (default 1)
+    ///
+    /// Returns:
+    ///     list: Up to `count` Row objects; empty at the start of the result
+    ///
+    /// Raises:
+    ///     NotSupportedError: If the cursor wasn't opened with scroll=True
+    #[pyo3(signature = (count=1))]
+    pub fn fetch_backward(&mut self, py: Python, count: u64) -> PyResult<Vec<Row>> {
+        self.require_scroll()?;
+        // Step back over the rows fetched ahead, and off the end if there
+        let ahead = self.rows.len() as u64;
+        let rewind = if self.past_end { ahead + 1 } else { ahead };
+        self.rows.clear();
+        let moves = if rewind > 0 {
+            Some(format!("MOVE BACKWARD {} IN {}", rewind, self.name))
+        } else {
+            None
+        };
+
+        let rows = self.run_fetch(py, moves, format!("FETCH BACKWARD {} FROM {}", count, self.name))?;
+        self.past_end = false;
+        self.beyond_result = false;
+        rows.iter().map(|row| Row::from_tokio_row(py, row, self.conversion)).collect()
+    }
+
+    /// Fetch the row at an absolute position (scroll cursors only)
+    ///
+    /// Positions start at 1; negative positions count from the end, so -1
+    /// is the last row. Iterating or `fetchmany` then continues after it.
+    ///
+    /// Args:
+    ///     position: Row number
+    ///
+    /// Returns:
+    ///     Row or None: None if the position is outside the result
+    ///
+    /// Raises:
+    ///     NotSupportedError: If the cursor wasn't opened with scroll=True
+    pub fn fetch_absolute(&mut self, py: Python, position: i64) -> PyResult<Option<Row>> {
+        self.require_scroll()?;
+        self.rows.clear();
+
+        let rows = self.run_fetch(py, None, format!("FETCH ABSOLUTE {} FROM {}", position, self.name))?;
+        self.past_end = false;
+        self.beyond_result = rows.is_empty() && position > 0;
+        rows.first().map(|row| Row::from_tokio_row(py, row, self.conversion)).transpose()
+    }
+
+    /// Close the cursor, committing the transaction it opened if any
+    pub fn close(&mut self) -> PyResult<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        self.rows.clear();
+
+        let client = Arc::clone(&self.client);
+        let sql = if self.owns_transaction {
+            "COMMIT".to_string()
+        } else {
+            format!("CLOSE {}", self.name)
+        };
+        self.runtime.block_on(async move {
+            let client = client.lock().await;
+            client.batch_execute(&sql).await.map_err(map_db_error)
+        })
+    }
+
+    /// Check if the cursor is closed
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Row>> {
+        self.next_row(py)
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        exc_type: Option<PyObject>,
+        _exc_val: Option<PyObject>,
+        _exc_tb: Option<PyObject>,
+    ) -> PyResult<bool> {
+        let result = self.close();
+        // Keep the original exception rather than a cleanup failure
+        if exc_type.is_none() {
+            result?;
+        }
+        Ok(false)
+    }
+}
+
+impl Cursor {
+    /// Declare a cursor for `query`, starting a transaction when needed
+    #[allow(clippy::too_many_arguments)]
+    pub fn open(
+        client: Arc<Mutex<Client>>,
+        runtime: RuntimeManager,
+        conn_state: Arc<ConnectionState>,
+        conversion: ConversionOptions,
+        query: &str,
+        params: Vec<Box<dyn postgres_types::ToSql + Sync + Send>>,
+        fetch_size: u64,
+        scroll: bool,
+    ) -> PyResult<Self> {
+        let name = format!("postpyro_cursor_{}", CURSOR_ID.fetch_add(1, Ordering::Relaxed));
+        let declare = format!(
+            "DECLARE {} {}SCROLL CURSOR FOR {}",
+            name,
+            if scroll { "" } else { "NO " },
+            query.trim_end().trim_end_matches(';')
+        );
+
+        let owns_transaction = runtime.block_on({
+            let client = Arc::clone(&client);
+            async move {
+                let client = client.lock().await;
+                let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = params
+                    .iter()
+                    .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                    .collect();
+
+                // Without a transaction DECLARE fails without side effects
+                match client.execute(declare.as_str(), &params_refs[..]).await {
+                    Ok(_) => Ok(false),
+                    Err(e) if e.code() == Some(&SqlState::NO_ACTIVE_SQL_TRANSACTION) => {
+                        client.batch_execute("BEGIN").await.map_err(map_db_error)?;
+                        if let Err(e) = client.execute(declare.as_str(), &params_refs[..]).await {
+                            let _ = client.batch_execute("ROLLBACK").await;
+                            return Err(map_db_error(e));
+                        }
+                        Ok(true)
+                    }
+                    Err(e) => Err(map_db_error(e)),
+                }
+            }
+        })?;
+
+        Ok(Cursor {
+            client,
+            runtime,
+            conn_state,
+            conversion,
+            name,
+            fetch_size,
+            scroll,
+            rows: VecDeque::new(),
+            exhausted: false,
+            past_end: false,
+            beyond_result: false,
+            closed: false,
+            owns_transaction,
+        })
+    }
+
+    fn next_row(&mut self, py: Python) -> PyResult<Option<Row>> {
+        if self.rows.is_empty() {
+            let batch = self.fetch(py, self.fetch_size)?;
+            self.rows.extend(batch);
+        }
+        self.rows
+            .pop_front()
+            .map(|row| Row::from_tokio_row(py, &row, self.conversion))
+            .transpose()
+    }
+
+    /// FETCH up to `count` rows forward; a forward-only cursor closes
+    /// once the server runs out
+    fn fetch(&mut self, py: Python, count: u64) -> PyResult<Vec<TokioRow>> {
+        if self.exhausted || count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let rows = self.run_fetch(py, None, format!("FETCH FORWARD {} FROM {}", count, self.name))?;
+        if (rows.len() as u64) < count {
+            if self.scroll {
+                self.past_end = !self.beyond_result;
+            } else {
+                self.exhausted = true;
+                self.close()?;
+            }
+        }
+        Ok(rows)
+    }
+
+    fn require_scroll(&self) -> PyResult<()> {
+        if self.scroll {
+            Ok(())
+        } else {
+            Err(not_supported_error("moving backward in a cursor opened without scroll=True"))
+        }
+    }
+
+    /// Run an optional MOVE and then a FETCH with the GIL released
+    fn run_fetch(&mut self, py: Python, moves: Option<String>, fetch: String) -> PyResult<Vec<TokioRow>> {
+        if self.closed {
+            return Ok(Vec::new());
+        }
+        self.conn_state.check_open()?;
+
+        let client = Arc::clone(&self.client);
+        let runtime = self.runtime.clone();
+        let result = py.allow_threads(move || {
+            runtime.block_on(async move {
+                let client = client.lock().await;
+                if let Some(moves) = moves {
+                    client.batch_execute(&moves).await?;
+                }
+                client.query(fetch.as_str(), &[]).await
+            })
+        });
+
+        result.map_err(|e| {
+            // Don't leave the connection in the failed transaction we opened
+            if self.owns_transaction {
+                let client = Arc::clone(&self.client);
+                let _ = self.runtime.block_on(async move {
+                    client.lock().await.batch_execute("ROLLBACK").await
+                });
+            }
+            self.closed = true;
+            map_db_error(e)
+        })
+    }
+}
+
+impl Drop for Cursor {
+    fn drop(&mut self) {
+        if !self.closed && !self.conn_state.is_closed() {
+            let _ = self.close();
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use pyo3::create_exception;
 use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
 use pyo3::PyErr;
+use tokio_postgres::error::{DbError, ErrorPosition};
 use tokio_postgres::Error as PgError;
 
 // Base Database Error - follows DB-API 2.0 specification (PEP 249)
@@ -13,6 +15,60 @@ create_exception!(PostPyro, InternalError, DatabaseError);
 create_exception!(PostPyro, ProgrammingError, DatabaseError);
 create_exception!(PostPyro, NotSupportedError, DatabaseError);
 
+// Raised by query_one for a result without exactly one row
+create_exception!(PostPyro, NoDataError, DataError);
+create_exception!(PostPyro, TooManyRowsError, DataError);
+
+// Server errors are created with (message, pgcode, pgerror) and the
+// diagnostic fields in SERVER_ERROR_FIELDS as arguments; this keeps the
+// message as the only argument and the rest as attributes
+const DATABASE_ERROR_INIT: &str = "\
+def __init__(self, message=None, pgcode=None, pgerror=None, detail=None, hint=None,
+             position=None, table_name=None, column_name=None, constraint_name=None):
+    if message is None:
+        Exception.__init__(self)
+    else:
+        Exception.__init__(self, message)
+    self.pgcode = pgcode
+    self.pgerror = pgerror
+    self.detail = detail
+    self.hint = hint
+    self.position = position
+    self.table_name = table_name
+    self.column_name = column_name
+    self.constraint_name = constraint_name
+";
+
+// Attributes set by DATABASE_ERROR_INIT, None unless the server sent them
+const SERVER_ERROR_FIELDS: [&str; 8] = [
+    "pgcode",
+    "pgerror",
+    "detail",
+    "hint",
+    "position",
+    "table_name",
+    "column_name",
+    "constraint_name",
+];
+
+/// Give DatabaseError, and so every driver exception, `pgcode` and `pgerror`
+/// attributes: the SQLSTATE and the server's own message, plus the
+/// server's diagnostic fields (`detail`, `hint`, `position`, `table_name`,
+/// `column_name`, `constraint_name`); all None for errors that didn't come
+/// from the server
+///
+/// Errors are often created without the GIL, so they can't be given
+/// attributes after the fact; the class's `__init__` sets them instead.
+pub fn add_server_error_attributes(py: Python) -> PyResult<()> {
+    let init = PyModule::from_code(py, DATABASE_ERROR_INIT, "errors.py", "PostPyro.errors")?.getattr("__init__")?;
+    let class = py.get_type::<DatabaseError>();
+    class.setattr("__init__", init)?;
+    for field in SERVER_ERROR_FIELDS {
+        class.setattr(field, py.None())?;
+    }
+    Ok(())
+}
+
 /// Map PostgreSQL errors to appropriate Python DB-API 2.0 exceptions
 pub fn map_db_error(error: PgError) -> PyErr {
     map_db_error_enhanced(error)
@@ -36,16 +92,115 @@ pub fn connection_closed_error() -> PyErr {
     InterfaceError::new_err("Connection is closed")
 }
 
+/// Create an error for a connection that failed in the background, e.g. a
+/// network reset or server shutdown
+pub fn connection_lost_error(reason: &str) -> PyErr {
+    OperationalError::new_err(format!("Connection lost: {}", reason))
+}
+
+/// Create an error for a connection that failed inside a transaction, which
+/// auto_reconnect doesn't replace until the caller has seen it
+pub fn transaction_lost_error(reason: &str) -> PyErr {
+    OperationalError::new_err(format!(
+        "Connection lost during a transaction, which the server rolled back: {}; the next call reconnects",
+        reason
+    ))
+}
+
+/// Create an error for TLS settings that can't be used, such as unreadable certificate files
+pub fn tls_config_error(details: &str) -> PyErr {
+    InterfaceError::new_err(format!("TLS configuration error: {}", details))
+}
+
+/// Create an error for an idle connection that no longer responds
+pub fn stale_connection_error(idle_seconds: f64) -> PyErr {
+    OperationalError::new_err(format!(
+        "Connection was idle for {:.0}s and no longer responds; it has been closed",
+        idle_seconds
+    ))
+}
+
+/// Create an error for a pooled connection used after it went back to the pool
+pub fn connection_released_error() -> PyErr {
+    InterfaceError::new_err("Connection has been released back to the pool")
+}
+
+/// Create an error for a pool that had no connection free within wait_timeout
+pub fn pool_timeout_error(wait_seconds: f64, max_size: usize) -> PyErr {
+    OperationalError::new_err(format!(
+        "Timed out after {}s waiting for a connection; all {} pool connections are in use",
+        wait_seconds, max_size
+    ))
+}
+
+/// Create an error for pyformat placeholders that don't match their parameters
+pub fn placeholder_error(details: &str) -> PyErr {
+    ProgrammingError::new_err(format!("Invalid query placeholders: {}", details))
+}
+
+/// Create an error for a connection attempt that didn't finish within connect_timeout
+pub fn connect_timeout_error(seconds: f64) -> PyErr {
+    OperationalError::new_err(format!("Timed out after {}s connecting to the server", seconds))
+}
+
+/// Create an error for a statement canceled after running past its timeout
+pub fn statement_timeout_error(milliseconds: u64) -> PyErr {
+    OperationalError::new_err(format!(
+        "Statement timed out after {} ms and was canceled",
+        milliseconds
+    ))
+}
+
+/// Create an error for when a pool is closed but an operation is attempted
+pub fn pool_closed_error() -> PyErr {
+    InterfaceError::new_err("Connection pool is closed")
+}
+
 /// Create an error for when a transaction is completed but operations are attempted
 pub fn transaction_completed_error() -> PyErr {
     ProgrammingError::new_err("Transaction is already committed or rolled back")
 }
 
+/// Create an error for starting a Transaction while autocommit is off
+pub fn autocommit_off_error() -> PyErr {
+    ProgrammingError::new_err("transaction() needs autocommit on; with autocommit off, use commit() and rollback()")
+}
+
+/// Create an error for changing autocommit mid-transaction
+pub fn transaction_in_progress_error() -> PyErr {
+    ProgrammingError::new_err("Can't change autocommit while a transaction is in progress; commit() or rollback() first")
+}
+
+/// Create an error for a query_one result without rows
+pub fn no_data_error() -> PyErr {
+    NoDataError::new_err("query returned no rows, expected exactly one")
+}
+
+/// Create an error for a query_one result with more than one row
+pub fn too_many_rows_error() -> PyErr {
+    TooManyRowsError::new_err("query returned more than one row, expected exactly one")
+}
+
 /// Create an error for unsupported operations
 pub fn not_supported_error(feature: &str) -> PyErr {
     NotSupportedError::new_err(format!("Feature not supported: {}", feature))
 }
 
+/// A parameter value that doesn't fit the type the server expects
+///
+/// Raised while encoding parameters, once the parameter types are known;
+/// `map_db_error` turns it into DataError.
+#[derive(Debug)]
+pub struct ConversionError(pub String);
+
+impl std::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
 /// Map PostgreSQL error to appropriate Python exception
 pub fn map_db_error_enhanced(error: PgError) -> PyErr {
     use std::time::Instant;
@@ -64,15 +219,34 @@ pub fn map_db_error_enhanced(error: PgError) -> PyErr {
         detailed_message
     };
 
+    // SQLSTATE, raw server message and diagnostic fields, in the order of
+    // SERVER_ERROR_FIELDS
+    let db_error = error.as_db_error();
+    let field = |get: fn(&DbError) -> Option<&str>| db_error.and_then(get).map(str::to_string);
+    let args = (
+        enhanced_message,
+        error.code().map(|code| code.code().to_string()),
+        db_error.map(|db_error| db_error.message().to_string()),
+        field(DbError::detail),
+        field(DbError::hint),
+        db_error.and_then(|db_error| match db_error.position() {
+            Some(ErrorPosition::Original(position)) => Some(*position),
+            _ => None,
+        }),
+        field(DbError::table),
+        field(DbError::column),
+        field(DbError::constraint),
+    );
     match error_class {
-        PostgreSQLErrorClass::ConnectionIssue => OperationalError::new_err(enhanced_message),
-        PostgreSQLErrorClass::SyntaxError => ProgrammingError::new_err(enhanced_message),
-        PostgreSQLErrorClass::ConstraintViolation => IntegrityError::new_err(enhanced_message),
-        PostgreSQLErrorClass::DataTypeIssue => DataError::new_err(enhanced_message),
-        PostgreSQLErrorClass::InsufficientResources => OperationalError::new_err(enhanced_message),
-        PostgreSQLErrorClass::SystemError => InternalError::new_err(enhanced_message),
-        PostgreSQLErrorClass::UnsupportedFeature => NotSupportedError::new_err(enhanced_message),
-        PostgreSQLErrorClass::GenericDatabase => DatabaseError::new_err(enhanced_message),
+        PostgreSQLErrorClass::ConnectionIssue => OperationalError::new_err(args),
+        PostgreSQLErrorClass::SyntaxError => ProgrammingError::new_err(args),
+        PostgreSQLErrorClass::ConstraintViolation => IntegrityError::new_err(args),
+        PostgreSQLErrorClass::DataTypeIssue => DataError::new_err(args),
+        PostgreSQLErrorClass::InsufficientResources => OperationalError::new_err(args),
+        PostgreSQLErrorClass::OperatorIntervention => OperationalError::new_err(args),
+        PostgreSQLErrorClass::SystemError => InternalError::new_err(args),
+        PostgreSQLErrorClass::UnsupportedFeature => NotSupportedError::new_err(args),
+        PostgreSQLErrorClass::GenericDatabase => DatabaseError::new_err(args),
     }
 }
 
@@ -84,6 +258,7 @@ enum PostgreSQLErrorClass {
     ConstraintViolation,
     DataTypeIssue,
     InsufficientResources,
+    OperatorIntervention,
     SystemError,
     UnsupportedFeature,
     GenericDatabase,
@@ -115,6 +290,9 @@ fn analyze_postgresql_error(error: &PgError) -> (PostgreSQLErrorClass, String) {
                 PostgreSQLErrorClass::InsufficientResources
             }
 
+            // Operator intervention (57xxx), including canceled statements
+            code if code.starts_with("57") => PostgreSQLErrorClass::OperatorIntervention,
+
             // System error (58xxx, XX000)
             code if code.starts_with("58") || code == "XX000" => PostgreSQLErrorClass::SystemError,
 
@@ -123,6 +301,8 @@ fn analyze_postgresql_error(error: &PgError) -> (PostgreSQLErrorClass, String) {
 
             _ => PostgreSQLErrorClass::GenericDatabase,
         }
+    } else if std::error::Error::source(error).is_some_and(|source| source.is::<ConversionError>()) {
+        PostgreSQLErrorClass::DataTypeIssue
     } else {
         PostgreSQLErrorClass::GenericDatabase
     };
@@ -155,7 +335,7 @@ fn get_error_severity(class: &PostgreSQLErrorClass) -> &'static str {
         PostgreSQLErrorClass::SystemError | PostgreSQLErrorClass::InsufficientResources => {
             "CRITICAL"
         }
-        PostgreSQLErrorClass::ConnectionIssue => "ERROR",
+        PostgreSQLErrorClass::ConnectionIssue | PostgreSQLErrorClass::OperatorIntervention => "ERROR",
         PostgreSQLErrorClass::ConstraintViolation | PostgreSQLErrorClass::SyntaxError => "ERROR",
         PostgreSQLErrorClass::DataTypeIssue => "WARNING",
         PostgreSQLErrorClass::UnsupportedFeature => "INFO",
@@ -185,6 +365,10 @@ fn get_error_suggestion(class: &PostgreSQLErrorClass, sqlstate: &str) -> String
         PostgreSQLErrorClass::InsufficientResources => {
             "Database server resources exhausted - contact administrator".to_string()
         }
+        PostgreSQLErrorClass::OperatorIntervention => match sqlstate {
+            "57014" => "The statement was canceled by a cancel request or a timeout".to_string(),
+            _ => "The server interrupted the operation - retry once it is available".to_string(),
+        },
         PostgreSQLErrorClass::SystemError => {
             "Internal database error - check server logs and contact administrator".to_string()
         }
@@ -0,0 +1,539 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use postgres_types::{FromSql, Kind, Type};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use pyo3::{IntoPy, PyAny, PyErr, PyObject, PyResult, Python};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::fmt;
+
+use crate::composite::CompositeFields;
+use crate::error::DataError;
+use crate::types::{decode_raw, ConversionOptions, EnumLabel, Interval, NumericText, Temporal};
+
+// Binary JSONB values start with a format version byte
+const JSONB_VERSION: u8 = 1;
+
+// So do binary JSONPATH values
+const JSONPATH_VERSION: u8 = 1;
+
+/// Text of a JSON or JSONB value, exactly as stored by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonText(pub String);
+
+impl<'a> FromSql<'a> for JsonText {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // JSON's binary format is the text itself; JSONB adds a version byte
+        let text = if *ty == Type::JSONB {
+            match raw.split_first() {
+                Some((&JSONB_VERSION, text)) => text,
+                Some((version, _)) => return Err(format!("unsupported jsonb version: {}", version).into()),
+                None => return Err("empty jsonb value".into()),
+            }
+        } else {
+            raw
+        };
+        Ok(JsonText(std::str::from_utf8(text)?.to_string()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::JSON || *ty == Type::JSONB
+    }
+}
+
+/// Write JSON text in the binary format of a JSON or JSONB parameter
+pub fn write_json(
+    text: &str,
+    ty: &Type,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    if *ty == Type::JSONB {
+        out.extend_from_slice(&[JSONB_VERSION]);
+    } else if *ty != Type::JSON {
+        return Err(format!("cannot bind a JSON value to type {}", ty).into());
+    }
+    out.extend_from_slice(text.as_bytes());
+    Ok(postgres_types::IsNull::No)
+}
+
+/// Write SQL/JSON path text (`$.tags[*] ? (@ == "x")`) as a JSONPATH parameter
+///
+/// The server parses the path, so syntax errors come back as database errors.
+pub fn write_json_path(
+    text: &str,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    out.extend_from_slice(&[JSONPATH_VERSION]);
+    out.extend_from_slice(text.as_bytes());
+    Ok(postgres_types::IsNull::No)
+}
+
+/// Parse JSON text into Python objects
+///
+/// Objects become dicts (keeping key order), arrays lists, and `null`
+/// becomes None. Integers stay exact up to the 64-bit range; larger ones
+/// and all other numbers become floats.
+pub fn json_to_py(py: Python, text: &str) -> PyResult<PyObject> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let value = PyJsonSeed(py)
+        .deserialize(&mut deserializer)
+        .and_then(|value| deserializer.end().map(|_| value))
+        .map_err(|e| invalid_json_error(text, &e))?;
+    Ok(value)
+}
+
+/// DataError for JSON text that couldn't be decoded, naming the token
+/// decoding stopped at
+///
+/// The server only produces standard JSON, but text from elsewhere can hold
+/// NaN or Infinity, and numbers beyond the range of a float can't be decoded
+/// either.
+fn invalid_json_error(text: &str, error: &serde_json::Error) -> PyErr {
+    let token = json_token_at(text, error.line(), error.column());
+    let hint = match token.trim_start_matches(['-', '+']) {
+        "NaN" | "Infinity" => "; NaN and Infinity aren't valid JSON",
+        _ => "",
+    };
+    if token.is_empty() {
+        DataError::new_err(format!("Invalid JSON value: {}", error))
+    } else if token.len() > 40 {
+        // Tokens are ASCII, so any byte index is a char boundary
+        DataError::new_err(format!("Invalid JSON value: {} (at `{}...`, {} characters)", error, &token[..32], token.len()))
+    } else {
+        DataError::new_err(format!("Invalid JSON value: {} (at `{}`){}", error, token, hint))
+    }
+}
+
+/// The bare word or number around a 1-based line and column of `text`
+fn json_token_at(text: &str, line: usize, column: usize) -> &str {
+    let is_token = |c: char| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.');
+    let line = match text.split('\n').nth(line.saturating_sub(1)) {
+        Some(line) => line,
+        None => return "",
+    };
+
+    let mut position = column.saturating_sub(1).min(line.len());
+    while !line.is_char_boundary(position) {
+        position -= 1;
+    }
+    let start = line[..position].trim_end_matches(is_token).len();
+    let end = position + line[position..].find(|c: char| !is_token(c)).unwrap_or(line.len() - position);
+    &line[start..end]
+}
+
+/// Serialize a Python object as JSON text
+///
+/// Accepts None, bool, int, float, str, lists and tuples, and dicts with
+/// str keys, nested in any combination. Anything else, and floats JSON
+/// can't represent (NaN, infinity), raise DataError.
+pub fn py_to_json(obj: &PyAny) -> PyResult<String> {
+    serde_json::to_string(&PyJson(obj))
+        .map_err(|e| crate::error::type_conversion_error("JSON-serializable value", &e.to_string()))
+}
+
+/// Builds Python objects directly from the JSON token stream
+struct PyJsonSeed<'py>(Python<'py>);
+
+impl<'de> DeserializeSeed<'de> for PyJsonSeed<'_> {
+    type Value = PyObject;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<PyObject, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PyJsonSeed<'_> {
+    type Value = PyObject;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<PyObject, E> {
+        Ok(self.0.None())
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<PyObject, E> {
+        Ok(v.into_py(self.0))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<PyObject, E> {
+        Ok(v.into_py(self.0))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<PyObject, E> {
+        Ok(v.into_py(self.0))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<PyObject, E> {
+        Ok(v.into_py(self.0))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<PyObject, E> {
+        Ok(v.into_py(self.0))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PyObject, A::Error> {
+        let list = PyList::empty(self.0);
+        while let Some(item) = seq.next_element_seed(PyJsonSeed(self.0))? {
+            list.append(item).map_err(de::Error::custom)?;
+        }
+        Ok(list.into())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<PyObject, A::Error> {
+        let dict = PyDict::new(self.0);
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(PyJsonSeed(self.0))?;
+            dict.set_item(key, value).map_err(de::Error::custom)?;
+        }
+        Ok(dict.into())
+    }
+}
+
+/// Serializes a Python object graph without building an intermediate value
+struct PyJson<'a>(&'a PyAny);
+
+impl Serialize for PyJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let obj = self.0;
+        if obj.is_none() {
+            serializer.serialize_unit()
+        } else if let Ok(b) = obj.downcast::<PyBool>() {
+            serializer.serialize_bool(b.is_true())
+        } else if let Ok(i) = obj.downcast::<PyInt>() {
+            if let Ok(v) = i.extract::<i64>() {
+                serializer.serialize_i64(v)
+            } else if let Ok(v) = i.extract::<u64>() {
+                serializer.serialize_u64(v)
+            } else {
+                Err(ser::Error::custom(format!("integer {} is out of range", i)))
+            }
+        } else if let Ok(f) = obj.downcast::<PyFloat>() {
+            let v = f.value();
+            if v.is_finite() {
+                serializer.serialize_f64(v)
+            } else {
+                Err(ser::Error::custom(format!("float {} is not valid JSON", v)))
+            }
+        } else if let Ok(s) = obj.downcast::<PyString>() {
+            serializer.serialize_str(s.to_str().map_err(ser::Error::custom)?)
+        } else if let Ok(list) = obj.downcast::<PyList>() {
+            let mut seq = serializer.serialize_seq(Some(list.len()))?;
+            for item in list {
+                seq.serialize_element(&PyJson(item))?;
+            }
+            seq.end()
+        } else if let Ok(tuple) = obj.downcast::<PyTuple>() {
+            let mut seq = serializer.serialize_seq(Some(tuple.len()))?;
+            for item in tuple {
+                seq.serialize_element(&PyJson(item))?;
+            }
+            seq.end()
+        } else if let Ok(dict) = obj.downcast::<PyDict>() {
+            let mut map = serializer.serialize_map(Some(dict.len()))?;
+            for (key, value) in dict {
+                let key = key
+                    .downcast::<PyString>()
+                    .map_err(|_| ser::Error::custom(format!("dict key {} is not a str", key)))?;
+                map.serialize_entry(key.to_str().map_err(ser::Error::custom)?, &PyJson(value))?;
+            }
+            map.end()
+        } else {
+            let type_name = obj.get_type().name().unwrap_or("object");
+            Err(ser::Error::custom(format!("object of type {} is not JSON serializable", type_name)))
+        }
+    }
+}
+
+/// Any non-NULL column value, as the raw bytes of its binary format
+struct RawValue<'a>(&'a [u8]);
+
+impl<'a> FromSql<'a> for RawValue<'a> {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(RawValue(raw))
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
+/// Serialize result rows as a JSON array of objects keyed by column name
+///
+/// Values are encoded straight from their binary format, without building
+/// Python objects: numbers (NUMERIC included) as JSON numbers, date/time
+/// values as ISO 8601 strings, intervals as ISO 8601 durations, json/jsonb
+/// as embedded JSON, bytea as "\x" plus hex, arrays as nested arrays and
+/// composites as objects. NaN and infinite numbers, which JSON lacks, become
+/// strings; values that can't be decoded become null, or raise DataError in
+/// strict mode.
+pub fn rows_to_json(rows: &[tokio_postgres::Row], options: ConversionOptions) -> PyResult<String> {
+    let mut out = String::from("[");
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('{');
+        for (idx, column) in row.columns().iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            push_json_string(&mut out, column.name());
+            out.push(':');
+
+            let start = out.len();
+            let encoded = match row.try_get::<_, Option<RawValue>>(idx) {
+                Ok(raw) => value_to_json(&mut out, column.type_(), raw.map(|raw| raw.0)),
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = encoded {
+                if options.strict {
+                    return Err(DataError::new_err(e.to_string()));
+                }
+                out.truncate(start);
+                out.push_str("null");
+            }
+        }
+        out.push('}');
+    }
+    out.push(']');
+    Ok(out)
+}
+
+/// Append one value as JSON; on error the output may be left partial
+fn value_to_json(out: &mut String, ty: &Type, raw: Option<&[u8]>) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let raw = match raw {
+        Some(raw) => raw,
+        None => {
+            out.push_str("null");
+            return Ok(());
+        }
+    };
+
+    match *ty {
+        Type::INT2 => out.push_str(&decode_raw::<i16>(ty, raw)?.to_string()),
+        Type::INT4 => out.push_str(&decode_raw::<i32>(ty, raw)?.to_string()),
+        Type::INT8 => out.push_str(&decode_raw::<i64>(ty, raw)?.to_string()),
+        Type::OID => out.push_str(&decode_raw::<u32>(ty, raw)?.to_string()),
+        Type::FLOAT4 => push_json_float(out, decode_raw::<f32>(ty, raw)? as f64),
+        Type::FLOAT8 => push_json_float(out, decode_raw::<f64>(ty, raw)?),
+        Type::NUMERIC => {
+            let numeric = decode_raw::<NumericText>(ty, raw)?.0;
+            if numeric.starts_with(|c: char| c == '-' || c.is_ascii_digit()) && numeric != "-Infinity" {
+                out.push_str(&numeric);
+            } else {
+                push_json_string(out, &numeric);
+            }
+        }
+        Type::BOOL => out.push_str(if decode_raw::<bool>(ty, raw)? { "true" } else { "false" }),
+        Type::UUID => push_json_string(out, &decode_raw::<uuid::Uuid>(ty, raw)?.to_string()),
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => {
+            let text = match decode_raw::<Temporal<NaiveDateTime>>(ty, raw)? {
+                Temporal::Value(dt) => {
+                    let suffix = if *ty == Type::TIMESTAMPTZ { "+00:00" } else { "" };
+                    format!("{}T{}{}", dt.date(), iso_time(dt.time()), suffix)
+                }
+                Temporal::Infinity => "infinity".to_string(),
+                Temporal::NegInfinity => "-infinity".to_string(),
+            };
+            push_json_string(out, &text);
+        }
+        Type::DATE => {
+            let text = match decode_raw::<Temporal<NaiveDate>>(ty, raw)? {
+                Temporal::Value(date) => date.to_string(),
+                Temporal::Infinity => "infinity".to_string(),
+                Temporal::NegInfinity => "-infinity".to_string(),
+            };
+            push_json_string(out, &text);
+        }
+        Type::TIME => push_json_string(out, &iso_time(decode_raw::<NaiveTime>(ty, raw)?)),
+        Type::INTERVAL => push_json_string(out, &decode_raw::<Interval>(ty, raw)?.to_iso8601()),
+        Type::JSON | Type::JSONB => out.push_str(&decode_raw::<JsonText>(ty, raw)?.0),
+        Type::BYTEA => push_json_string(out, &format!("\\x{}", hex::encode(raw))),
+        _ => match ty.kind() {
+            Kind::Array(member) => array_to_json(out, member, raw)?,
+            Kind::Composite(fields) => {
+                let values = decode_raw::<CompositeFields>(ty, raw)?.0;
+                if values.len() != fields.len() {
+                    return Err(format!("composite value of type {} doesn't match its fields", ty).into());
+                }
+                out.push('{');
+                for (i, (field, value)) in fields.iter().zip(values).enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    push_json_string(out, field.name());
+                    out.push(':');
+                    value_to_json(out, field.type_(), value)?;
+                }
+                out.push('}');
+            }
+            Kind::Enum(_) => push_json_string(out, &decode_raw::<EnumLabel>(ty, raw)?.0),
+            _ => push_json_string(out, &decode_raw::<String>(ty, raw)?),
+        },
+    }
+    Ok(())
+}
+
+/// Append an array value, nesting one JSON array per dimension
+fn array_to_json(out: &mut String, member: &Type, raw: &[u8]) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    // Header: ndim, has-null flag, element OID, then one (length, lower
+    // bound) per dimension
+    let mut reader = raw;
+    let ndim = read_i32(&mut reader)?;
+    read_i32(&mut reader)?;
+    read_i32(&mut reader)?;
+    let mut dims = Vec::new();
+    for _ in 0..ndim {
+        dims.push(read_i32(&mut reader)?.max(0) as usize);
+        read_i32(&mut reader)?;
+    }
+
+    if dims.is_empty() {
+        out.push_str("[]");
+        Ok(())
+    } else {
+        elements_to_json(out, member, &dims, &mut reader)
+    }
+}
+
+/// Append the elements of one array dimension; elements are stored in
+/// row-major order as (length, bytes), length -1 for NULL
+fn elements_to_json(
+    out: &mut String,
+    member: &Type,
+    dims: &[usize],
+    reader: &mut &[u8],
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    out.push('[');
+    for i in 0..dims[0] {
+        if i > 0 {
+            out.push(',');
+        }
+        if dims.len() > 1 {
+            elements_to_json(out, member, &dims[1..], reader)?;
+        } else {
+            let len = read_i32(reader)?;
+            let value = if len < 0 {
+                None
+            } else {
+                let value = reader.get(..len as usize).ok_or("invalid array value")?;
+                *reader = &reader[len as usize..];
+                Some(value)
+            };
+            value_to_json(out, member, value)?;
+        }
+    }
+    out.push(']');
+    Ok(())
+}
+
+fn read_i32(reader: &mut &[u8]) -> Result<i32, Box<dyn std::error::Error + Sync + Send>> {
+    let (head, rest) = reader.split_first_chunk::<4>().ok_or("invalid array value")?;
+    *reader = rest;
+    Ok(i32::from_be_bytes(*head))
+}
+
+/// Append a float; NaN and infinities, which JSON lacks, as strings
+fn push_json_float(out: &mut String, value: f64) {
+    if value.is_finite() {
+        out.push_str(&serde_json::to_string(&value).unwrap_or_else(|_| value.to_string()));
+    } else if value.is_nan() {
+        push_json_string(out, "NaN");
+    } else if value > 0.0 {
+        push_json_string(out, "Infinity");
+    } else {
+        push_json_string(out, "-Infinity");
+    }
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push_str(&serde_json::to_string(value).unwrap_or_default());
+}
+
+/// Time of day as `HH:MM:SS`, with microseconds when there are any (like Python's isoformat)
+fn iso_time(time: NaiveTime) -> String {
+    let micros = time.nanosecond() / 1000;
+    if micros == 0 {
+        time.format("%H:%M:%S").to_string()
+    } else {
+        format!("{}.{:06}", time.format("%H:%M:%S"), micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_text_strips_jsonb_version() {
+        let raw = b"\x01{\"a\": [1, null]}";
+        assert_eq!(JsonText::from_sql(&Type::JSONB, raw).unwrap().0, "{\"a\": [1, null]}");
+        assert_eq!(JsonText::from_sql(&Type::JSON, b"null").unwrap().0, "null");
+        assert!(JsonText::from_sql(&Type::JSONB, b"\x02{}").is_err());
+        assert!(JsonText::from_sql(&Type::JSONB, b"").is_err());
+        assert!(!JsonText::accepts(&Type::TEXT));
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut buf = bytes::BytesMut::new();
+        write_json("[1]", &Type::JSONB, &mut buf).unwrap();
+        assert_eq!(&buf[..], b"\x01[1]");
+
+        buf.clear();
+        write_json("[1]", &Type::JSON, &mut buf).unwrap();
+        assert_eq!(&buf[..], b"[1]");
+
+        assert!(write_json("[1]", &Type::TEXT, &mut buf).is_err());
+
+        buf.clear();
+        write_json_path("$.a", &mut buf).unwrap();
+        assert_eq!(&buf[..], b"\x01$.a");
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        let json = |ty: &Type, raw: Option<&[u8]>| {
+            let mut out = String::new();
+            value_to_json(&mut out, ty, raw).map(|_| out)
+        };
+
+        assert_eq!(json(&Type::INT4, Some(&7i32.to_be_bytes())).unwrap(), "7");
+        assert_eq!(json(&Type::FLOAT8, Some(&f64::NAN.to_be_bytes())).unwrap(), "\"NaN\"");
+        assert_eq!(json(&Type::TEXT, Some(b"a\"b")).unwrap(), "\"a\\\"b\"");
+        assert_eq!(json(&Type::TEXT, None).unwrap(), "null");
+        assert_eq!(json(&Type::JSONB, Some(b"\x01{\"k\": 1}")).unwrap(), "{\"k\": 1}");
+        assert_eq!(json(&Type::BYTEA, Some(b"\x01\xff")).unwrap(), "\"\\\\x01ff\"");
+        // 2000-01-01 00:00:01.5 as microseconds since the PostgreSQL epoch
+        assert_eq!(
+            json(&Type::TIMESTAMPTZ, Some(&1_500_000i64.to_be_bytes())).unwrap(),
+            "\"2000-01-01T00:00:01.500000+00:00\""
+        );
+        assert!(json(&Type::INT4, Some(b"\x01")).is_err());
+
+        // int4[][] {{1,2},{3,NULL}}
+        let mut raw = Vec::new();
+        for word in [2, 1, Type::INT4.oid() as i32, 2, 1, 2, 1] {
+            raw.extend_from_slice(&word.to_be_bytes());
+        }
+        for value in [1i32, 2, 3] {
+            raw.extend_from_slice(&4i32.to_be_bytes());
+            raw.extend_from_slice(&value.to_be_bytes());
+        }
+        raw.extend_from_slice(&(-1i32).to_be_bytes());
+        assert_eq!(json(&Type::INT4_ARRAY, Some(&raw)).unwrap(), "[[1,2],[3,null]]");
+    }
+
+    #[test]
+    fn test_json_token_at() {
+        let error = |text: &str| {
+            let error = serde_json::from_str::<serde_json::Value>(text).unwrap_err();
+            json_token_at(text, error.line(), error.column()).to_string()
+        };
+        assert_eq!(error("{\"a\": NaN}"), "NaN");
+        assert_eq!(error("[1, -Infinity]"), "-Infinity");
+        assert_eq!(error("{\"x\":\n  1e400}"), "1e400");
+        assert_eq!(json_token_at("", 1, 1), "");
+    }
+}
@@ -1,20 +1,42 @@
 use pyo3::prelude::*;
 
+#[cfg(feature = "arrow")]
+mod arrow;
+mod catalog;
+mod composite;
 mod connection;
+mod copy;
+mod csv;
+mod cursor;
 mod error;
+mod json;
+mod notify;
+mod paramstyle;
 mod pool;
+mod querylog;
+mod quote;
+mod result;
 mod row;
 mod runtime;
+mod state;
+mod statement;
+#[cfg(test)]
+mod testing;
+mod tls;
 mod transaction;
 mod types;
 
 use connection::PgConnection;
+use copy::CopyOutIterator;
+use cursor::Cursor;
 use error::{
-    DataError, DatabaseError, IntegrityError, InterfaceError, InternalError, NotSupportedError,
-    OperationalError, ProgrammingError,
+    DataError, DatabaseError, IntegrityError, InterfaceError, InternalError, NoDataError, NotSupportedError,
+    OperationalError, ProgrammingError, TooManyRowsError,
 };
-use pool::ConnectionPool;
-use row::Row;
+use pool::{ConnectionPool, PooledConnection};
+use result::QueryResult;
+use row::{ReusedRowIterator, Row};
+use statement::PreparedStatement;
 use transaction::Transaction;
 
 #[pymodule]
@@ -22,9 +44,19 @@ fn PostPyro(_py: Python, m: &PyModule) -> PyResult<()> {
     // Classes
     m.add_class::<PgConnection>()?;
     m.add_class::<ConnectionPool>()?;
+    m.add_class::<PooledConnection>()?;
+    m.add_class::<PreparedStatement>()?;
+    m.add_class::<Cursor>()?;
+    m.add_class::<QueryResult>()?;
     m.add_class::<Row>()?;
+    m.add_class::<ReusedRowIterator>()?;
+    m.add_class::<CopyOutIterator>()?;
     m.add_class::<Transaction>()?;
 
+    // Functions
+    m.add_function(wrap_pyfunction!(quote::py_quote_ident, m)?)?;
+    m.add_function(wrap_pyfunction!(quote::py_quote_literal, m)?)?;
+
     // Exceptions (DB-API 2.0 compliant)
     m.add("DatabaseError", _py.get_type::<DatabaseError>())?;
     m.add("InterfaceError", _py.get_type::<InterfaceError>())?;
@@ -34,12 +66,15 @@ fn PostPyro(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add("InternalError", _py.get_type::<InternalError>())?;
     m.add("ProgrammingError", _py.get_type::<ProgrammingError>())?;
     m.add("NotSupportedError", _py.get_type::<NotSupportedError>())?;
+    m.add("NoDataError", _py.get_type::<NoDataError>())?;
+    m.add("TooManyRowsError", _py.get_type::<TooManyRowsError>())?;
+    error::add_server_error_attributes(_py)?;
 
     // Constants (DB-API 2.0)
     m.add("__version__", "0.2.0")?;
     m.add("apilevel", "2.0")?;
     m.add("threadsafety", 2)?;
-    m.add("paramstyle", "format")?;
+    m.add("paramstyle", "numeric")?;
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,110 @@
+use parking_lot::Mutex;
+use pyo3::prelude::*;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::Notify;
+use tokio_postgres::Notification;
+
+/// What a full notification queue does with a new notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Discard the oldest pending notification to make room
+    DropOldest,
+    /// Discard the incoming notification
+    DropNewest,
+}
+
+impl Overflow {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "drop_oldest" => Ok(Overflow::DropOldest),
+            "drop_newest" => Ok(Overflow::DropNewest),
+            _ => Err(crate::error::type_conversion_error("'drop_oldest' or 'drop_newest'", name)),
+        }
+    }
+}
+
+/// Notifications received by a connection's background task, waiting to be consumed
+///
+/// The queue is bounded so an application that LISTENs but never consumes
+/// can't grow memory without limit. Overflow always drops rather than
+/// blocks: blocking would stop the background task from reading the socket,
+/// stalling every query on the connection.
+pub struct NotificationQueue {
+    pending: Mutex<VecDeque<Notification>>,
+    arrived: Notify,
+    capacity: usize,
+    overflow: Overflow,
+    dropped: AtomicU64,
+}
+
+impl NotificationQueue {
+    pub fn new(capacity: usize, overflow: Overflow) -> Self {
+        Self {
+            pending: Mutex::new(VecDeque::new()),
+            arrived: Notify::new(),
+            capacity,
+            overflow,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue a notification and wake one waiter, applying the overflow
+    /// policy when the queue is full
+    pub fn push(&self, notification: Notification) {
+        {
+            let mut pending = self.pending.lock();
+            if pending.len() >= self.capacity {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                match self.overflow {
+                    Overflow::DropOldest => {
+                        pending.pop_front();
+                    }
+                    Overflow::DropNewest => return,
+                }
+            }
+            pending.push_back(notification);
+        }
+        self.arrived.notify_one();
+    }
+
+    /// Number of notifications discarded because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Number of notifications waiting to be consumed
+    pub fn len(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Take the oldest pending notification, if any
+    pub fn pop(&self) -> Option<Notification> {
+        self.pending.lock().pop_front()
+    }
+
+    /// Take every pending notification, oldest first
+    pub fn drain(&self) -> Vec<Notification> {
+        self.pending.lock().drain(..).collect()
+    }
+
+    /// Wait until a notification is available and take it
+    pub async fn recv(&self) -> Notification {
+        loop {
+            if let Some(notification) = self.pop() {
+                return notification;
+            }
+            self.arrived.notified().await;
+        }
+    }
+}
+
+/// Convert a notification into a `(channel, payload, pid)` tuple
+pub fn notification_to_py(py: Python, notification: &Notification) -> PyObject {
+    (
+        notification.channel(),
+        notification.payload(),
+        notification.process_id(),
+    )
+        .into_py(py)
+}
@@ -0,0 +1,276 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use std::borrow::Cow;
+
+use crate::error::{placeholder_error, type_conversion_error};
+
+/// How a connection's queries write parameter placeholders
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamStyle {
+    /// `$1`, `$2`, ... with a list of values, as the server takes them
+    Numeric,
+    /// psycopg2's `%s` with a list or tuple, or `%(name)s` with a dict
+    Pyformat,
+}
+
+impl ParamStyle {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "numeric" => Ok(ParamStyle::Numeric),
+            "pyformat" => Ok(ParamStyle::Pyformat),
+            _ => Err(type_conversion_error("'numeric' or 'pyformat'", name)),
+        }
+    }
+
+    /// Turn a query and its parameters into `$N` placeholders and a list
+    ///
+    /// Numeric queries pass through unchanged and take a list. Pyformat
+    /// queries are rewritten only when parameters are given, as psycopg2
+    /// does, so a parameterless query keeps any `%%` as written. A pyformat
+    /// query without placeholders given a list is taken to use `$N` ones and
+    /// is also sent as written.
+    pub fn bind<'q, 'py>(
+        self,
+        py: Python<'py>,
+        query: &'q str,
+        params: Option<&'py PyAny>,
+    ) -> PyResult<(Cow<'q, str>, Option<&'py PyList>)> {
+        let params = match params {
+            Some(params) if !params.is_none() => params,
+            _ => return Ok((Cow::Borrowed(query), None)),
+        };
+        if self == ParamStyle::Numeric || (params.is_instance_of::<PyList>() && !has_placeholders(query)) {
+            return Ok((Cow::Borrowed(query), Some(list_params(params)?)));
+        }
+
+        let (sql, placeholders) = translate_pyformat(query).map_err(|e| placeholder_error(&e))?;
+        Ok((Cow::Owned(sql), Some(placeholders.values(py, params)?)))
+    }
+
+    /// Turn a query's placeholders into `$N` ones ahead of time, for a
+    /// statement prepared once and run with `bind_translated`
+    ///
+    /// None when the query is sent as written: numeric queries, and pyformat
+    /// ones without `%` placeholders, which keep any `%%` as written.
+    pub fn translate(self, query: &str) -> PyResult<(Cow<'_, str>, Option<Placeholders>)> {
+        if self == ParamStyle::Numeric || !has_placeholders(query) {
+            return Ok((Cow::Borrowed(query), None));
+        }
+        let (sql, placeholders) = translate_pyformat(query).map_err(|e| placeholder_error(&e))?;
+        Ok((Cow::Owned(sql), Some(placeholders)))
+    }
+
+    /// The name `paramstyle=` takes for this style
+    pub fn name(self) -> &'static str {
+        match self {
+            ParamStyle::Numeric => "numeric",
+            ParamStyle::Pyformat => "pyformat",
+        }
+    }
+}
+
+/// The parameters of a statement translated with `ParamStyle::translate`,
+/// as the list its `$N` placeholders take; None for no parameters
+pub fn bind_translated<'py>(
+    placeholders: Option<&Placeholders>,
+    py: Python<'py>,
+    params: Option<&'py PyAny>,
+) -> PyResult<Option<&'py PyList>> {
+    match params {
+        Some(params) if !params.is_none() => match placeholders {
+            Some(placeholders) => placeholders.values(py, params).map(Some),
+            None => list_params(params).map(Some),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Parameters for a query with `$N` placeholders, which take a list
+fn list_params(params: &PyAny) -> PyResult<&PyList> {
+    match params.downcast::<PyList>() {
+        Ok(list) => Ok(list),
+        Err(_) if params.is_instance_of::<PyDict>() => Err(pyo3::exceptions::PyTypeError::new_err(
+            "dict parameters need a connection with paramstyle='pyformat'",
+        )),
+        Err(_) => Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "params must be a list, not {}",
+            params.get_type().name()?
+        ))),
+    }
+}
+
+/// Placeholders of a pyformat query, in the order of their `$N`
+#[derive(Debug, PartialEq)]
+pub enum Placeholders {
+    /// Number of `%s` placeholders
+    Positional(usize),
+    /// Distinct names of `%(name)s` placeholders; a repeated name reuses its `$N`
+    Named(Vec<String>),
+}
+
+impl Placeholders {
+    /// Order the given parameters by placeholder
+    fn values<'py>(&self, py: Python<'py>, params: &'py PyAny) -> PyResult<&'py PyList> {
+        let values = match (self, params.downcast::<PyDict>()) {
+            (Placeholders::Named(names), Ok(dict)) => {
+                let values = PyList::empty(py);
+                for name in names {
+                    let value = dict
+                        .get_item(name)?
+                        .ok_or_else(|| placeholder_error(&format!("no parameter named '{}'", name)))?;
+                    values.append(value)?;
+                }
+                values
+            }
+            (Placeholders::Named(_), Err(_)) => {
+                return Err(placeholder_error("%(name)s placeholders need a dict of parameters"));
+            }
+            (Placeholders::Positional(_), Ok(dict)) if !dict.is_empty() => {
+                return Err(placeholder_error("%s placeholders need a list or tuple of parameters"));
+            }
+            (Placeholders::Positional(_), Ok(_)) => PyList::empty(py),
+            (Placeholders::Positional(count), Err(_)) => {
+                let values = if let Ok(tuple) = params.downcast::<PyTuple>() {
+                    PyList::new(py, tuple)
+                } else {
+                    params.downcast::<PyList>().map_err(|_| {
+                        pyo3::exceptions::PyTypeError::new_err(format!(
+                            "params must be a list, tuple or dict, not {}",
+                            params.get_type().name().unwrap_or("?")
+                        ))
+                    })?
+                };
+                if values.len() != *count {
+                    return Err(placeholder_error(&format!(
+                        "the query has {} placeholders but {} parameters were given",
+                        count,
+                        values.len()
+                    )));
+                }
+                values
+            }
+        };
+        Ok(values)
+    }
+}
+
+/// Whether a query has a `%s` or `%(name)s` placeholder
+fn has_placeholders(query: &str) -> bool {
+    let mut rest = query;
+    while let Some(percent) = rest.find('%') {
+        match rest[percent + 1..].chars().next() {
+            Some('s') | Some('(') => return true,
+            Some('%') => rest = &rest[percent + 2..],
+            _ => rest = &rest[percent + 1..],
+        }
+    }
+    false
+}
+
+/// Rewrite `%s` and `%(name)s` placeholders as `$1`, `$2`, ...
+///
+/// Follows psycopg2: every `%` in the query is formatting, including ones
+/// inside string literals, so a literal percent sign is written `%%`. A
+/// query can't mix positional and named placeholders.
+pub fn translate_pyformat(query: &str) -> Result<(String, Placeholders), String> {
+    let mut sql = String::with_capacity(query.len());
+    let mut positional = 0;
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = query;
+
+    while let Some(percent) = rest.find('%') {
+        sql.push_str(&rest[..percent]);
+        let spec = &rest[percent + 1..];
+        if let Some(after) = spec.strip_prefix('%') {
+            sql.push('%');
+            rest = after;
+        } else if let Some(after) = spec.strip_prefix('s') {
+            positional += 1;
+            sql.push_str(&format!("${}", positional));
+            rest = after;
+        } else if let Some(named) = spec.strip_prefix('(') {
+            let close = named.find(')').ok_or("unterminated %(name)s placeholder")?;
+            let name = &named[..close];
+            let after = named[close + 1..]
+                .strip_prefix('s')
+                .ok_or_else(|| format!("placeholder %({}) must end in 's'", name))?;
+            let index = match names.iter().position(|n| n == name) {
+                Some(index) => index,
+                None => {
+                    names.push(name.to_string());
+                    names.len() - 1
+                }
+            };
+            sql.push_str(&format!("${}", index + 1));
+            rest = after;
+        } else {
+            let found: String = spec.chars().next().map(|c| format!("%{}", c)).unwrap_or("%".to_string());
+            return Err(format!("unsupported placeholder '{}'; write %% for a literal %", found));
+        }
+    }
+    sql.push_str(rest);
+
+    match (positional, names.is_empty()) {
+        (0, false) => Ok((sql, Placeholders::Named(names))),
+        (_, true) => Ok((sql, Placeholders::Positional(positional))),
+        _ => Err("a query can't mix %s and %(name)s placeholders".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_positional() {
+        assert_eq!(
+            translate_pyformat("SELECT * FROM t WHERE a = %s AND b > %s").unwrap(),
+            ("SELECT * FROM t WHERE a = $1 AND b > $2".to_string(), Placeholders::Positional(2))
+        );
+        assert_eq!(
+            translate_pyformat("SELECT 1").unwrap(),
+            ("SELECT 1".to_string(), Placeholders::Positional(0))
+        );
+        assert_eq!(
+            translate_pyformat("SELECT '100%%', name LIKE %s").unwrap(),
+            ("SELECT '100%', name LIKE $1".to_string(), Placeholders::Positional(1))
+        );
+    }
+
+    #[test]
+    fn test_translate_named() {
+        assert_eq!(
+            translate_pyformat("UPDATE t SET a = %(a)s WHERE id = %(id)s OR parent = %(id)s").unwrap(),
+            (
+                "UPDATE t SET a = $1 WHERE id = $2 OR parent = $2".to_string(),
+                Placeholders::Named(vec!["a".to_string(), "id".to_string()])
+            )
+        );
+        assert_eq!(
+            translate_pyformat("SELECT %(ünï)s").unwrap(),
+            ("SELECT $1".to_string(), Placeholders::Named(vec!["ünï".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_has_placeholders() {
+        assert!(has_placeholders("SELECT %s"));
+        assert!(has_placeholders("SELECT %(a)s"));
+        assert!(has_placeholders("SELECT '%%', %s"));
+        assert!(!has_placeholders("SELECT $1"));
+        assert!(!has_placeholders("SELECT '%%s', $1 % 2"));
+        assert!(!has_placeholders("SELECT 50%"));
+    }
+
+    #[test]
+    fn test_translate_errors() {
+        assert!(translate_pyformat("SELECT %s, %(a)s").is_err());
+        assert!(translate_pyformat("SELECT %(a)d").is_err());
+        assert!(translate_pyformat("SELECT %(a").is_err());
+        assert!(translate_pyformat("SELECT 50%").is_err());
+        assert_eq!(
+            translate_pyformat("SELECT %d").unwrap_err(),
+            "unsupported placeholder '%d'; write %% for a literal %"
+        );
+    }
+}
@@ -1,19 +1,26 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
 use pyo3::prelude::*;
-use pyo3::types::PyList;
-use tokio_postgres::{NoTls, Config};
-use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use tokio_postgres::Config;
+use deadpool::managed::{Hook, HookError, QueueMode, TimeoutType};
+use deadpool_postgres::{ClientWrapper, Manager, ManagerConfig, Object, Pool, PoolError, RecyclingMethod, Runtime};
 
-use crate::error::map_db_error;
-use crate::row::Row;
+use crate::error::{map_db_error, pool_closed_error, pool_timeout_error, InterfaceError};
+use crate::paramstyle::ParamStyle;
+use crate::row::{query_single, Row};
 use crate::runtime::RuntimeManager;
-use crate::types::py_objects_to_postgres_values;
+use crate::tls::TlsOptions;
+use crate::transaction::{apply_default_isolation, SharedClient, Transaction};
+use crate::types::{py_objects_to_postgres_values, ConversionOptions};
 
 /// High-performance connection pool for managing database connections
 #[pyclass(name = "ConnectionPool")]
 pub struct ConnectionPool {
     pool: Arc<Pool>,
     runtime: RuntimeManager,
+    breaker: Arc<CircuitBreaker>,
+    paramstyle: ParamStyle,
 }
 
 #[pymethods]
@@ -21,43 +28,190 @@ impl ConnectionPool {
     /// Create a new connection pool
     ///
     /// Args:
-    ///     connection_string: PostgreSQL connection string
+    ///     connection_string: PostgreSQL connection string; sslmode and
+    ///         sslrootcert, sslcert and sslkey set up TLS as for `Connection`
     ///     max_size: Maximum number of connections in pool (default: 10)
-    ///     min_size: Minimum number of connections in pool (default: 0)
+    ///     min_size: Connections opened up front, so the first requests
+    ///         don't wait for a connection to be established (default: 0)
+    ///     default_isolation_level: Isolation level every pooled connection
+    ///         starts its transactions with, e.g. "REPEATABLE READ"
+    ///         (default: server setting)
+    ///     queue_mode: Order idle connections are handed out in: "fifo"
+    ///         spreads load across all connections, "lifo" reuses the most
+    ///         recently returned one to keep a small, warm working set
+    ///         (default: "fifo")
+    ///     failure_threshold: Consecutive failures to get a connection after
+    ///         which the pool stops trying for a while and fails fast
+    ///         (default: None, always try)
+    ///     backoff_initial: Seconds to fail fast after the threshold is
+    ///         reached; doubles with every failed probe (default: 1.0)
+    ///     backoff_max: Upper limit for the fail-fast period in seconds
+    ///         (default: 30.0)
+    ///     ssl_cert: Client certificate PEM file; overrides sslcert in the
+    ///         connection string (default: None)
+    ///     ssl_key: Private key PEM file for ssl_cert; overrides sslkey in
+    ///         the connection string (default: None)
+    ///     recycling_method: Check run on an idle connection before it's
+    ///         handed out again: "fast" only checks it isn't closed,
+    ///         "verified" also runs `SELECT 1`, "clean" resets the session
+    ///         like DISCARD ALL (default: "fast")
+    ///     wait_timeout: Seconds to wait for a connection when all are in use,
+    ///         after which OperationalError is raised (default: None, wait
+    ///         indefinitely)
+    ///     create_timeout: Seconds allowed for opening a new connection
+    ///         (default: None)
+    ///     recycle_timeout: Seconds allowed for the recycling check; a
+    ///         connection that takes longer is discarded (default: None)
+    ///     on_connect: SQL statements run once on every new connection, not
+    ///         on every checkout, e.g. `["SET search_path TO app, public"]`.
+    ///         A callable is called without arguments for each new
+    ///         connection and returns the statements. A statement that fails
+    ///         discards the connection. With recycling_method="clean" they
+    ///         also run after every reset, since DISCARD ALL undoes them
+    ///         (default: None)
+    ///     paramstyle: How queries write parameter placeholders, for the
+    ///         pool's own methods and the connections and transactions it
+    ///         hands out: "numeric" or "pyformat", as for `Connection`
+    ///         (default: "numeric")
     ///
     /// Returns:
     ///     ConnectionPool: New connection pool
     ///
     /// Raises:
-    ///     InterfaceError: If pool creation fails
+    ///     InterfaceError: If pool creation fails, min_size connections
+    ///         can't be opened, or a certificate or key file is missing or
+    ///         malformed
+    ///     ValueError: If a backoff setting isn't positive, a timeout is
+    ///         negative, or min_size is larger than max_size
     #[new]
-    #[pyo3(signature = (connection_string, max_size=10, min_size=0))]
-    pub fn new(connection_string: &str, max_size: usize, min_size: usize) -> PyResult<Self> {
+    #[pyo3(signature = (
+        connection_string,
+        max_size=10,
+        min_size=0,
+        default_isolation_level=None,
+        queue_mode="fifo",
+        failure_threshold=None,
+        backoff_initial=1.0,
+        backoff_max=30.0,
+        ssl_cert=None,
+        ssl_key=None,
+        recycling_method="fast",
+        wait_timeout=None,
+        create_timeout=None,
+        recycle_timeout=None,
+        on_connect=None,
+        paramstyle="numeric",
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        connection_string: &str,
+        max_size: usize,
+        min_size: usize,
+        default_isolation_level: Option<&str>,
+        queue_mode: &str,
+        failure_threshold: Option<u32>,
+        backoff_initial: f64,
+        backoff_max: f64,
+        ssl_cert: Option<&str>,
+        ssl_key: Option<&str>,
+        recycling_method: &str,
+        wait_timeout: Option<f64>,
+        create_timeout: Option<f64>,
+        recycle_timeout: Option<f64>,
+        on_connect: Option<&PyAny>,
+        paramstyle: &str,
+    ) -> PyResult<Self> {
         let runtime = RuntimeManager::new();
 
         // Parse connection string
-        let config: Config = connection_string.parse().map_err(|e| {
+        let (connection_string, mut tls) = TlsOptions::split(connection_string)?;
+        tls.set_client_cert(ssl_cert, ssl_key);
+        let mut config: Config = connection_string.parse().map_err(|e| {
             pyo3::exceptions::PyValueError::new_err(format!("Invalid connection string: {}", e))
         })?;
+        tls.apply(&mut config);
+        if let Some(level) = default_isolation_level {
+            apply_default_isolation(&mut config, level)?;
+        }
 
-        // Create pool
-        let mgr_config = ManagerConfig {
-            recycling_method: RecyclingMethod::Fast,
+        if min_size > max_size {
+            return Err(pyo3::exceptions::PyValueError::new_err("min_size must not be larger than max_size"));
+        }
+
+        let queue_mode = match queue_mode.to_lowercase().as_str() {
+            "fifo" => QueueMode::Fifo,
+            "lifo" => QueueMode::Lifo,
+            _ => return Err(crate::error::type_conversion_error("'fifo' or 'lifo'", queue_mode)),
+        };
+
+        if !(backoff_initial > 0.0 && backoff_initial.is_finite() && backoff_max >= backoff_initial && backoff_max.is_finite()) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "backoff_initial must be positive and no larger than backoff_max",
+            ));
+        }
+        let breaker = CircuitBreaker::new(
+            failure_threshold.map(|t| t.max(1)),
+            Duration::from_secs_f64(backoff_initial),
+            Duration::from_secs_f64(backoff_max),
+        );
+
+        let recycling_method = match recycling_method.to_lowercase().as_str() {
+            "fast" => RecyclingMethod::Fast,
+            "verified" => RecyclingMethod::Custom("SELECT 1".to_string()),
+            "clean" => RecyclingMethod::Clean,
+            _ => return Err(crate::error::type_conversion_error("'fast', 'verified' or 'clean'", recycling_method)),
         };
-        let mgr = Manager::from_config(config, NoTls, mgr_config);
+        let wait_timeout = timeout_duration("wait_timeout", wait_timeout)?;
+        let create_timeout = timeout_duration("create_timeout", create_timeout)?;
+        let recycle_timeout = timeout_duration("recycle_timeout", recycle_timeout)?;
+        let on_connect = on_connect.map(OnConnect::from_py).transpose()?;
+        let paramstyle = ParamStyle::from_name(paramstyle)?;
+
+        let clean = matches!(recycling_method, RecyclingMethod::Clean);
+
+        // Create pool
+        let mgr_config = ManagerConfig { recycling_method };
+        let mgr = Manager::from_config(config, tls.connector()?, mgr_config);
         
+        let mut builder = Pool::builder(mgr)
+            .max_size(max_size)
+            .queue_mode(queue_mode)
+            .wait_timeout(wait_timeout)
+            .create_timeout(create_timeout)
+            .recycle_timeout(recycle_timeout)
+            .runtime(Runtime::Tokio1);
+        if let Some(on_connect) = on_connect {
+            let on_connect = Arc::new(on_connect);
+            // DISCARD ALL undoes the setup, so it's redone after every reset
+            if clean {
+                builder = builder.post_recycle(on_connect_hook(Arc::clone(&on_connect)));
+            }
+            builder = builder.post_create(on_connect_hook(on_connect));
+        }
+
         let pool = runtime.block_on(async {
-            Pool::builder(mgr)
-                .max_size(max_size)
+            builder
                 .build()
                 .map_err(|e| {
                     pyo3::exceptions::PyConnectionError::new_err(format!("Pool creation error: {}", e))
                 })
         })?;
 
+        // Open min_size connections at once and return them to the pool idle
+        if min_size > 0 {
+            runtime.block_on(async {
+                let warm = futures_util::future::try_join_all((0..min_size).map(|_| pool.get())).await;
+                warm.map(drop).map_err(|e| {
+                    InterfaceError::new_err(format!("Could not open min_size={} connections: {}", min_size, e))
+                })
+            })?;
+        }
+
         Ok(Self {
             pool: Arc::new(pool),
             runtime,
+            breaker: Arc::new(breaker),
+            paramstyle,
         })
     }
 
@@ -69,21 +223,23 @@ impl ConnectionPool {
     ///
     /// Returns:
     ///     int: Number of rows affected
-    pub fn execute(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<u64> {
+    pub fn execute(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<u64> {
+        self.check_open()?;
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
+
         let postgres_params = if let Some(p) = params {
             let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
-            py_objects_to_postgres_values(py, &params_vec)?
+            py_objects_to_postgres_values(py, &params_vec, ConversionOptions::default())?
         } else {
             Vec::new()
         };
 
         let pool = Arc::clone(&self.pool);
+        let breaker = Arc::clone(&self.breaker);
         let query = query.to_string();
 
         self.runtime.block_on(async move {
-            let client = pool.get().await.map_err(|e| {
-                pyo3::exceptions::PyConnectionError::new_err(format!("Failed to get connection: {}", e))
-            })?;
+            let client = get_client(&pool, &breaker).await?;
 
             let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
                 .iter()
@@ -104,21 +260,23 @@ impl ConnectionPool {
     ///
     /// Returns:
     ///     list: List of Row objects
-    pub fn query(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<PyObject> {
+    pub fn query(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<PyObject> {
+        self.check_open()?;
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
+
         let postgres_params = if let Some(p) = params {
             let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
-            py_objects_to_postgres_values(py, &params_vec)?
+            py_objects_to_postgres_values(py, &params_vec, ConversionOptions::default())?
         } else {
             Vec::new()
         };
 
         let pool = Arc::clone(&self.pool);
+        let breaker = Arc::clone(&self.breaker);
         let query = query.to_string();
 
         let rows = self.runtime.block_on(async move {
-            let client = pool.get().await.map_err(|e| {
-                pyo3::exceptions::PyConnectionError::new_err(format!("Failed to get connection: {}", e))
-            })?;
+            let client = get_client(&pool, &breaker).await?;
 
             let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
                 .iter()
@@ -133,11 +291,11 @@ impl ConnectionPool {
         let py_rows = if rows.len() < 100 {
             let mut result = Vec::with_capacity(rows.len());
             for row in rows {
-                result.push(Row::from_tokio_row(py, &row)?);
+                result.push(Row::from_tokio_row(py, &row, ConversionOptions::default())?);
             }
             result
         } else {
-            Row::from_tokio_rows(py, &rows)?
+            Row::from_tokio_rows(py, &rows, ConversionOptions::default())?
         };
 
         Ok(py_rows.into_py(py))
@@ -151,36 +309,71 @@ impl ConnectionPool {
     ///
     /// Returns:
     ///     Row: Single row result
-    pub fn query_one(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<Py<Row>> {
+    ///
+    /// Raises:
+    ///     NoDataError: If the query returned no rows
+    ///     TooManyRowsError: If the query returned more than one row
+    pub fn query_one(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<Py<Row>> {
+        self.check_open()?;
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
+
         let postgres_params = if let Some(p) = params {
             let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
-            py_objects_to_postgres_values(py, &params_vec)?
+            py_objects_to_postgres_values(py, &params_vec, ConversionOptions::default())?
         } else {
             Vec::new()
         };
 
         let pool = Arc::clone(&self.pool);
+        let breaker = Arc::clone(&self.breaker);
         let query = query.to_string();
 
         let row = self.runtime.block_on(async move {
-            let client = pool.get().await.map_err(|e| {
-                pyo3::exceptions::PyConnectionError::new_err(format!("Failed to get connection: {}", e))
-            })?;
+            let client = get_client(&pool, &breaker).await?;
 
             let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
                 .iter()
                 .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
                 .collect();
 
-            client.query_one(&query, &params_refs[..])
-                .await
-                .map_err(map_db_error)
+            query_single(&client, query.as_str(), &params_refs[..]).await
         })?;
 
-        let row_obj = Row::from_tokio_row(py, &row)?;
+        let row_obj = Row::from_tokio_row(py, &row, ConversionOptions::default())?;
         Ok(Py::new(py, row_obj)?)
     }
 
+    /// Check a connection out of the pool for a series of statements
+    ///
+    /// Everything run through the returned connection uses one server
+    /// session, so transactions and session settings carry over between
+    /// calls. Use it as a context manager to hand the connection back when
+    /// the block ends:
+    ///
+    ///     with pool.acquire() as conn:
+    ///         with conn.transaction() as txn:
+    ///             txn.execute("UPDATE ...")
+    ///
+    /// Returns:
+    ///     PooledConnection: The checked-out connection
+    ///
+    /// Raises:
+    ///     InterfaceError: If the pool is closed
+    ///     ConnectionError: If no connection could be obtained
+    pub fn acquire(&self) -> PyResult<PooledConnection> {
+        self.check_open()?;
+
+        let pool = Arc::clone(&self.pool);
+        let breaker = Arc::clone(&self.breaker);
+        let object = self.runtime.block_on(async move { get_client(&pool, &breaker).await })?;
+
+        Ok(PooledConnection {
+            object: Arc::new(tokio::sync::Mutex::new(Some(object))),
+            runtime: self.runtime.clone(),
+            paramstyle: self.paramstyle,
+        })
+    }
+
     /// Get pool status information
     ///
     /// Returns:
@@ -191,6 +384,565 @@ impl ConnectionPool {
         info.set_item("size", status.size)?;
         info.set_item("available", status.available)?;
         info.set_item("max_size", status.max_size)?;
+        info.set_item("circuit_open", self.breaker.is_open(Instant::now()))?;
+        info.set_item("consecutive_failures", self.breaker.failures())?;
         Ok(info.to_object(py))
     }
-}
\ No newline at end of file
+
+    /// Change the maximum number of connections at runtime
+    ///
+    /// Growing takes effect immediately. Shrinking closes idle connections
+    /// right away; connections in use are closed as they are returned,
+    /// until the pool is down to the new size.
+    ///
+    /// Args:
+    ///     max_size: New maximum number of connections (at least 1)
+    ///
+    /// Raises:
+    ///     ValueError: If max_size is less than 1
+    pub fn resize(&self, max_size: usize) -> PyResult<()> {
+        if max_size < 1 {
+            return Err(pyo3::exceptions::PyValueError::new_err("max_size must be at least 1"));
+        }
+        self.pool.resize(max_size);
+        Ok(())
+    }
+
+    /// Close the pool
+    ///
+    /// Idle connections are closed right away and connections in use are
+    /// closed as they are returned. Further queries on the pool raise
+    /// InterfaceError. Connections checked out with `acquire()` keep working,
+    /// so work in progress can finish, and are closed on `release()`.
+    /// Closing an already closed pool does nothing.
+    pub fn close(&self) {
+        self.pool.close();
+    }
+
+    /// Check if the pool is closed
+    ///
+    /// Returns:
+    ///     bool: True if the pool is closed
+    pub fn is_closed(&self) -> bool {
+        self.pool.is_closed()
+    }
+
+    /// Context manager entry - returns this same pool object
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Context manager exit
+    ///
+    /// Closes the pool. Exceptions raised inside the block are never
+    /// suppressed.
+    fn __exit__(&self, _exc_type: Option<PyObject>, _exc_val: Option<PyObject>, _exc_tb: Option<PyObject>) -> bool {
+        self.close();
+        false
+    }
+}
+
+/// Session setup run on every new physical connection of a pool
+enum OnConnect {
+    Statements(Vec<String>),
+    // Called without arguments, returns the statements
+    Callable(PyObject),
+}
+
+impl OnConnect {
+    fn from_py(obj: &PyAny) -> PyResult<Self> {
+        if obj.is_callable() {
+            Ok(OnConnect::Callable(obj.into()))
+        } else {
+            Ok(OnConnect::Statements(obj.extract()?))
+        }
+    }
+
+    /// The statements to run on a new connection
+    ///
+    /// Connections are created inside `block_on` on the calling thread,
+    /// which holds the GIL, so calling back into Python doesn't block.
+    fn statements(&self) -> Result<Vec<String>, String> {
+        match self {
+            OnConnect::Statements(statements) => Ok(statements.clone()),
+            OnConnect::Callable(callable) => Python::with_gil(|py| {
+                callable.call0(py)?.extract::<Vec<String>>(py)
+            })
+            .map_err(|e| format!("on_connect failed: {}", e)),
+        }
+    }
+}
+
+/// Hook running the on_connect statements on a connection
+fn on_connect_hook(on_connect: Arc<OnConnect>) -> Hook<Manager> {
+    Hook::async_fn(move |client: &mut ClientWrapper, _| {
+        let on_connect = Arc::clone(&on_connect);
+        Box::pin(async move {
+            for sql in on_connect.statements().map_err(HookError::Message)? {
+                client.batch_execute(&sql).await.map_err(HookError::Backend)?;
+            }
+            Ok(())
+        })
+    })
+}
+
+/// Parse a timeout given in seconds
+fn timeout_duration(name: &str, seconds: Option<f64>) -> PyResult<Option<Duration>> {
+    match seconds {
+        Some(seconds) if seconds < 0.0 || !seconds.is_finite() => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "{} must be a non-negative number of seconds",
+            name
+        ))),
+        Some(seconds) => Ok(Some(Duration::from_secs_f64(seconds))),
+        None => Ok(None),
+    }
+}
+
+/// Take a connection from the pool, going through the circuit breaker
+async fn get_client(pool: &Pool, breaker: &CircuitBreaker) -> PyResult<Object> {
+    match breaker.check(Instant::now()) {
+        Err(retry_in) if retry_in.is_zero() => {
+            return Err(pyo3::exceptions::PyConnectionError::new_err(
+                "Database unavailable: failing fast after repeated connection failures while another caller probes it",
+            ));
+        }
+        Err(retry_in) => {
+            return Err(pyo3::exceptions::PyConnectionError::new_err(format!(
+                "Database unavailable: failing fast after repeated connection failures, next attempt in {:.1}s",
+                retry_in.as_secs_f64()
+            )));
+        }
+        Ok(()) => {}
+    }
+
+    match pool.get().await {
+        Ok(client) => {
+            breaker.record_success();
+            Ok(client)
+        }
+        // Every connection is busy; that says nothing about the database
+        Err(PoolError::Timeout(TimeoutType::Wait)) => {
+            breaker.end_probe();
+            let waited = pool.timeouts().wait.unwrap_or_default();
+            Err(pool_timeout_error(waited.as_secs_f64(), pool.status().max_size))
+        }
+        Err(e) => {
+            breaker.record_failure(Instant::now());
+            Err(pyo3::exceptions::PyConnectionError::new_err(format!("Failed to get connection: {}", e)))
+        }
+    }
+}
+
+/// A connection checked out of a `ConnectionPool` with `acquire()`
+///
+/// The connection goes back to the pool on `release()`, when its `with`
+/// block ends (even if an exception propagates), or when the object is
+/// garbage collected; after `release()` it can't be used.
+#[pyclass(name = "PooledConnection")]
+pub struct PooledConnection {
+    // None once released
+    object: Arc<tokio::sync::Mutex<Option<Object>>>,
+    runtime: RuntimeManager,
+    paramstyle: ParamStyle,
+}
+
+#[pymethods]
+impl PooledConnection {
+    /// Execute a query that doesn't return rows
+    ///
+    /// Args:
+    ///     query: SQL query string
+    ///     params: Query parameters (optional)
+    ///
+    /// Returns:
+    ///     int: Number of rows affected
+    ///
+    /// Raises:
+    ///     InterfaceError: If the connection has been released
+    #[pyo3(signature = (query, params=None))]
+    pub fn execute(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<u64> {
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
+        let postgres_params = if let Some(p) = params {
+            let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
+            py_objects_to_postgres_values(py, &params_vec, ConversionOptions::default())?
+        } else {
+            Vec::new()
+        };
+
+        let client = self.client();
+        self.runtime.block_on(async move {
+            let client = client.lock().await?;
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+
+            client.execute(query.as_ref(), &params_refs[..]).await.map_err(map_db_error)
+        })
+    }
+
+    /// Execute a query and return all rows
+    ///
+    /// Args:
+    ///     query: SQL query string
+    ///     params: Query parameters (optional)
+    ///
+    /// Returns:
+    ///     list: List of Row objects
+    ///
+    /// Raises:
+    ///     InterfaceError: If the connection has been released
+    #[pyo3(signature = (query, params=None))]
+    pub fn query(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<PyObject> {
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
+        let postgres_params = if let Some(p) = params {
+            let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
+            py_objects_to_postgres_values(py, &params_vec, ConversionOptions::default())?
+        } else {
+            Vec::new()
+        };
+
+        let client = self.client();
+        let rows = self.runtime.block_on(async move {
+            let client = client.lock().await?;
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+
+            client.query(query.as_ref(), &params_refs[..]).await.map_err(map_db_error)
+        })?;
+
+        Ok(Row::from_tokio_rows(py, &rows, ConversionOptions::default())?.into_py(py))
+    }
+
+    /// Execute a query and return exactly one row
+    ///
+    /// Args:
+    ///     query: SQL query string
+    ///     params: Query parameters (optional)
+    ///
+    /// Returns:
+    ///     Row: Single row result
+    ///
+    /// Raises:
+    ///     InterfaceError: If the connection has been released
+    ///     NoDataError: If the query returned no rows
+    ///     TooManyRowsError: If the query returned more than one row
+    #[pyo3(signature = (query, params=None))]
+    pub fn query_one(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<Py<Row>> {
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
+        let postgres_params = if let Some(p) = params {
+            let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
+            py_objects_to_postgres_values(py, &params_vec, ConversionOptions::default())?
+        } else {
+            Vec::new()
+        };
+
+        let client = self.client();
+        let row = self.runtime.block_on(async move {
+            let client = client.lock().await?;
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+
+            query_single(&client, query.as_ref(), &params_refs[..]).await
+        })?;
+
+        Py::new(py, Row::from_tokio_row(py, &row, ConversionOptions::default())?)
+    }
+
+    /// Begin a transaction on this connection
+    ///
+    /// Returns:
+    ///     Transaction: As a context manager it commits on success and
+    ///         rolls back on an exception
+    ///
+    /// Raises:
+    ///     InterfaceError: If the connection has been released
+    pub fn transaction(&self) -> PyResult<Transaction> {
+        Transaction::new(self.client(), self.runtime.clone(), ConversionOptions::default(), self.paramstyle)
+    }
+
+    /// Return the connection to the pool
+    ///
+    /// Releasing an already released connection does nothing.
+    pub fn release(&self) {
+        let object = Arc::clone(&self.object);
+        self.runtime.block_on(async move {
+            object.lock().await.take();
+        });
+    }
+
+    /// Check if the connection has been returned to the pool
+    ///
+    /// Returns:
+    ///     bool: True once released
+    pub fn is_released(&self) -> bool {
+        self.object.try_lock().map(|object| object.is_none()).unwrap_or(false)
+    }
+
+    /// Context manager entry - returns this same connection object
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Context manager exit - releases the connection
+    ///
+    /// If an exception is propagating, any transaction still open is
+    /// rolled back first so it doesn't leak into the connection's next
+    /// user. Exceptions are never suppressed.
+    fn __exit__(&self, exc_type: Option<PyObject>, _exc_val: Option<PyObject>, _exc_tb: Option<PyObject>) -> bool {
+        if exc_type.is_some() {
+            let client = self.client();
+            // Keep the original exception rather than a rollback failure
+            let _ = self.runtime.block_on(async move {
+                let client = client.lock().await?;
+                client.batch_execute("ROLLBACK").await.map_err(map_db_error)
+            });
+        }
+        self.release();
+        false
+    }
+}
+
+impl PooledConnection {
+    fn client(&self) -> SharedClient {
+        SharedClient::Pooled(Arc::clone(&self.object))
+    }
+}
+
+/// Stops connection attempts for a while after repeated failures
+///
+/// Once `threshold` consecutive attempts to get a connection have failed,
+/// the breaker opens: attempts fail immediately until the backoff period
+/// ends, then one attempt goes through as a probe while the others go on
+/// failing until it's over. A failed probe doubles the period (up to `max`),
+/// a success closes the breaker again. This keeps a recovering database from
+/// being hit by every caller at once.
+struct CircuitBreaker {
+    threshold: Option<u32>,
+    initial: Duration,
+    max: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    failures: u32,
+    open_until: Option<Instant>,
+    // A probe has been let through and its outcome isn't recorded yet
+    probing: bool,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: Option<u32>, initial: Duration, max: Duration) -> Self {
+        Self {
+            threshold,
+            initial,
+            max,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Ok if an attempt may go ahead, otherwise the time until the next probe
+    ///
+    /// Once the backoff period is over, the first caller gets Ok and becomes
+    /// the probe; later callers get a zero wait until its outcome is recorded
+    /// with `record_success`, `record_failure` or `end_probe`.
+    fn check(&self, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+        match state.open_until {
+            Some(until) if now < until => Err(until - now),
+            Some(_) if state.probing => Err(Duration::ZERO),
+            Some(_) => {
+                state.probing = true;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn record_success(&self) {
+        *self.state.lock() = BreakerState::default();
+    }
+
+    fn record_failure(&self, now: Instant) {
+        let mut state = self.state.lock();
+        state.probing = false;
+        state.failures = state.failures.saturating_add(1);
+        if let Some(threshold) = self.threshold {
+            if state.failures >= threshold {
+                let doublings = (state.failures - threshold).min(31);
+                let backoff = self.initial.saturating_mul(1 << doublings).min(self.max);
+                state.open_until = Some(now + backoff);
+            }
+        }
+    }
+
+    /// Let the next caller probe again after an attempt that said nothing
+    /// about the database, such as a wait for a busy pool timing out
+    fn end_probe(&self) {
+        self.state.lock().probing = false;
+    }
+
+    fn is_open(&self, now: Instant) -> bool {
+        let state = self.state.lock();
+        state.probing || state.open_until.is_some_and(|until| now < until)
+    }
+
+    fn failures(&self) -> u32 {
+        self.state.lock().failures
+    }
+}
+
+impl ConnectionPool {
+    fn check_open(&self) -> PyResult<()> {
+        if self.pool.is_closed() {
+            Err(pool_closed_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run, test_dsn, with_gil};
+
+    #[test]
+    fn test_circuit_breaker_backoff() {
+        let breaker = CircuitBreaker::new(Some(3), Duration::from_secs(1), Duration::from_secs(5));
+        let start = Instant::now();
+
+        // Below the threshold every attempt goes through
+        breaker.record_failure(start);
+        breaker.record_failure(start);
+        assert!(breaker.check(start).is_ok());
+
+        // Opens for the initial period, then lets a probe through
+        breaker.record_failure(start);
+        assert_eq!(breaker.check(start), Err(Duration::from_secs(1)));
+        let probe = start + Duration::from_secs(1);
+        assert!(breaker.check(probe).is_ok());
+        // Only one probe at a time
+        assert_eq!(breaker.check(probe), Err(Duration::ZERO));
+        assert!(breaker.is_open(probe));
+
+        // Failed probes double the period up to the maximum
+        breaker.record_failure(probe);
+        assert_eq!(breaker.check(probe), Err(Duration::from_secs(2)));
+        breaker.record_failure(probe);
+        breaker.record_failure(probe);
+        assert_eq!(breaker.check(probe), Err(Duration::from_secs(5)));
+
+        breaker.record_success();
+        assert!(breaker.check(probe).is_ok());
+        assert_eq!(breaker.failures(), 0);
+    }
+
+    #[test]
+    fn test_circuit_breaker_lets_one_concurrent_probe_through() {
+        let breaker = CircuitBreaker::new(Some(1), Duration::from_secs(1), Duration::from_secs(5));
+        let start = Instant::now();
+        breaker.record_failure(start);
+
+        let expired = start + Duration::from_secs(1);
+        let barrier = std::sync::Barrier::new(2);
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let callers: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        breaker.check(expired)
+                    })
+                })
+                .collect();
+            callers.into_iter().map(|caller| caller.join().unwrap()).collect()
+        });
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1, "{:?}", results);
+
+        // A probe that says nothing about the database lets the next one through
+        breaker.end_probe();
+        assert!(breaker.check(expired).is_ok());
+    }
+
+    #[test]
+    fn test_circuit_breaker_disabled() {
+        let breaker = CircuitBreaker::new(None, Duration::from_secs(1), Duration::from_secs(5));
+        let now = Instant::now();
+        for _ in 0..10 {
+            breaker.record_failure(now);
+        }
+        assert!(breaker.check(now).is_ok());
+        assert_eq!(breaker.failures(), 10);
+    }
+
+    #[test]
+    #[ignore = "needs a server, see src/testing.rs"]
+    fn test_on_connect_runs_once_per_physical_connection() {
+        with_gil(|py| {
+            let globals = [
+                ("ConnectionPool", py.get_type::<ConnectionPool>().to_object(py)),
+                ("dsn", test_dsn().to_object(py)),
+            ];
+            let code = r#"
+calls = []
+def on_connect():
+    calls.append(1)
+    return ["SET application_name TO 'on_connect_test'"]
+
+pool = ConnectionPool(dsn, max_size=2, on_connect=on_connect)
+pids = set()
+for _ in range(5):
+    row = pool.query_one("SELECT pg_backend_pid() AS pid, current_setting('application_name') AS app")
+    assert row["app"] == "on_connect_test", row
+    pids.add(row["pid"])
+assert len(pids) == 1 and len(calls) == 1, (pids, calls)
+
+# A second connection only opens while the first is checked out
+first, second = pool.acquire(), pool.acquire()
+for conn in (first, second):
+    pids.add(conn.query_one("SELECT pg_backend_pid() AS pid")["pid"])
+first.release()
+second.release()
+pool.query("SELECT 1")
+assert len(pids) == 2 and len(calls) == 2, (pids, calls)
+pool.close()
+
+# DISCARD ALL on every checkout resets the session, so on_connect runs again
+calls.clear()
+pool = ConnectionPool(dsn, max_size=1, on_connect=on_connect, recycling_method="clean")
+for _ in range(3):
+    assert pool.query_one("SELECT current_setting('application_name') AS app")["app"] == "on_connect_test"
+assert len(calls) == 3, calls
+pool.close()
+"#;
+            run(py, globals, code);
+        });
+    }
+
+    #[test]
+    #[ignore = "needs a server, see src/testing.rs"]
+    fn test_pyformat_applies_to_pool_and_pooled_connections() {
+        with_gil(|py| {
+            let globals = [
+                ("ConnectionPool", py.get_type::<ConnectionPool>().to_object(py)),
+                ("dsn", test_dsn().to_object(py)),
+            ];
+            let code = r#"
+pool = ConnectionPool(dsn, max_size=1, paramstyle="pyformat")
+assert pool.query_one("SELECT %(n)s::int AS n", {"n": 1})["n"] == 1
+assert pool.query("SELECT %s::int AS n", (2,))[0]["n"] == 2
+assert pool.execute("SELECT %s::int", [3]) == 1
+with pool.acquire() as conn:
+    assert conn.query_one("SELECT %s::int AS n", (4,))["n"] == 4
+    with conn.transaction() as txn:
+        assert txn.query_one("SELECT %(n)s::int AS n", {"n": 5})["n"] == 5
+pool.close()
+"#;
+            run(py, globals, code);
+        });
+    }
+}
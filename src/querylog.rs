@@ -0,0 +1,420 @@
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyInt, PyList, PyString};
+use std::collections::HashMap;
+
+// Python's logging.DEBUG
+const DEBUG: u8 = 10;
+
+static LOGGER: GILOnceCell<PyObject> = GILOnceCell::new();
+
+/// Per-connection query logging, with redaction of sensitive parameters
+///
+/// Statements are logged at DEBUG level on the `PostPyro` logger of Python's
+/// `logging` module. A parameter is shown as `***` when its 1-based position
+/// is listed, or when it is bound to a column whose name contains one of the
+/// patterns (case-insensitive). Columns are recognised from comparisons and
+/// assignments (`password = $2`, `$2 = password`, `password IN ($2, $3)`,
+/// `password = crypt($2, ...)`, casts included) and from `INSERT ... (cols)
+/// VALUES (...)`. When patterns are set, a parameter whose column isn't
+/// recognised is hidden too.
+#[derive(Debug, Clone, Default)]
+pub struct QueryLog {
+    enabled: bool,
+    positions: Vec<usize>,
+    patterns: Vec<String>,
+}
+
+impl QueryLog {
+    /// Build the logging config from the connection's `log_queries` and
+    /// `redact_params` arguments; `redact_params` holds ints and/or strings
+    pub fn new(enabled: bool, redact_params: Option<&PyList>) -> PyResult<Self> {
+        let mut log = QueryLog {
+            enabled,
+            ..Default::default()
+        };
+
+        for item in redact_params.into_iter().flatten() {
+            if let Ok(position) = item.downcast::<PyInt>() {
+                let position: usize = position.extract()?;
+                if position == 0 {
+                    return Err(pyo3::exceptions::PyValueError::new_err(
+                        "redact_params positions are 1-based",
+                    ));
+                }
+                log.positions.push(position);
+            } else if let Ok(pattern) = item.downcast::<PyString>() {
+                log.patterns.push(pattern.to_str()?.to_lowercase());
+            } else {
+                return Err(pyo3::exceptions::PyTypeError::new_err(
+                    "redact_params entries must be parameter positions (int) or column name patterns (str)",
+                ));
+            }
+        }
+
+        Ok(log)
+    }
+
+    /// Log a statement and its parameters if DEBUG logging is enabled
+    pub fn log(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let logger = LOGGER
+            .get_or_try_init(py, || {
+                Ok::<_, PyErr>(py.import("logging")?.call_method1("getLogger", ("PostPyro",))?.into())
+            })?
+            .as_ref(py);
+        if !logger.call_method1("isEnabledFor", (DEBUG,))?.is_true()? {
+            return Ok(());
+        }
+
+        let params = params.map(|p| p.iter().collect::<Vec<_>>()).unwrap_or_default();
+        let redacted = self.redacted(query, params.len());
+        let shown = params
+            .iter()
+            .zip(redacted)
+            .map(|(value, redact)| {
+                if redact {
+                    Ok("***".to_string())
+                } else {
+                    value.repr()?.extract::<String>()
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        logger.call_method1("debug", ("%s params=[%s]", query, shown.join(", ")))?;
+        Ok(())
+    }
+
+    /// Which of the first `count` parameters must be hidden
+    ///
+    /// With column patterns set, a parameter is also hidden wherever its
+    /// column can't be told, so values reach the log only when known safe.
+    fn redacted(&self, query: &str, count: usize) -> Vec<bool> {
+        let columns = if self.patterns.is_empty() {
+            HashMap::new()
+        } else {
+            placeholder_columns(query)
+        };
+        let sensitive = |column: &Option<String>| match column {
+            Some(column) => self.patterns.iter().any(|p| column.contains(p.as_str())),
+            None => true,
+        };
+
+        (1..=count)
+            .map(|position| {
+                self.positions.contains(&position)
+                    || (!self.patterns.is_empty()
+                        && columns.get(&position).is_none_or(|occurrences| occurrences.iter().any(sensitive)))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Param(usize),
+    Op(String),
+    Punct(char),
+}
+
+/// Map placeholder numbers to the (lowercased) column each occurrence is
+/// bound to, or None where it can't be told
+fn placeholder_columns(query: &str) -> HashMap<usize, Vec<Option<String>>> {
+    let tokens = tokenize(query);
+    // Column bound to each placeholder token, by token index
+    let mut owners: HashMap<usize, String> = HashMap::new();
+
+    // `column = $n`, `column LIKE lower($n)`, `column IN ($n, ...)`,
+    // `column::text = $n::text`, `SET column = $n`, and `$n = column`
+    for i in 1..tokens.len() {
+        let compares = match &tokens[i] {
+            Token::Op(op) => matches!(op.as_str(), "=" | "<>" | "!=" | "<" | ">" | "<=" | ">=" | ":="),
+            Token::Ident(word) => matches!(word.as_str(), "like" | "ilike" | "in"),
+            _ => false,
+        };
+        if !compares {
+            continue;
+        }
+        let mut left = skip_casts_back(&tokens, i - 1);
+        if tokens[i] == Token::Ident("in".to_string()) && tokens.get(left) == Some(&Token::Ident("not".to_string())) {
+            left = skip_casts_back(&tokens, left.saturating_sub(1));
+        }
+        match (tokens.get(left), tokens.get(i + 1)) {
+            (Some(Token::Ident(column)), _) => {
+                for param in operand_params(&tokens, i + 1) {
+                    owners.insert(param, column.clone());
+                }
+            }
+            (Some(Token::Param(_)), Some(Token::Ident(_))) => {
+                let mut j = i + 1;
+                while tokens.get(j + 1) == Some(&Token::Punct('.')) {
+                    j += 2;
+                }
+                if let (Some(Token::Ident(column)), false) = (tokens.get(j), tokens.get(j + 1) == Some(&Token::Punct('('))) {
+                    owners.insert(left, column.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // INSERT INTO table (col, ...) VALUES ($1, ...), (...); every
+    // placeholder in a value, e.g. crypt($2, ...), belongs to its column
+    let ident = |i: usize, word: &str| matches!(tokens.get(i), Some(Token::Ident(w)) if w == word);
+    if let Some(start) = (0..tokens.len()).find(|&i| ident(i, "insert") && ident(i + 1, "into")) {
+        let mut i = start + 3;
+        while tokens.get(i) == Some(&Token::Punct('.')) {
+            i += 2;
+        }
+        if tokens.get(i) == Some(&Token::Punct('(')) {
+            let mut names = Vec::new();
+            i += 1;
+            while let Some(token) = tokens.get(i) {
+                match token {
+                    Token::Ident(name) => names.push(name.clone()),
+                    Token::Punct(')') => break,
+                    _ => {}
+                }
+                i += 1;
+            }
+
+            if ident(i + 1, "values") {
+                i += 2;
+                let mut depth = 0;
+                let mut element = 0;
+                let mut element_params: Vec<usize> = Vec::new();
+                while let Some(token) = tokens.get(i) {
+                    match token {
+                        Token::Punct('(') => {
+                            depth += 1;
+                            if depth == 1 {
+                                element = 0;
+                                element_params.clear();
+                            }
+                        }
+                        Token::Punct(')') | Token::Punct(',') if depth == 1 => {
+                            if let Some(name) = names.get(element) {
+                                for param in element_params.drain(..) {
+                                    owners.insert(param, name.clone());
+                                }
+                            }
+                            element += 1;
+                            element_params.clear();
+                            if *token == Token::Punct(')') {
+                                depth -= 1;
+                            }
+                        }
+                        Token::Punct(')') => depth -= 1,
+                        Token::Param(_) if depth >= 1 => element_params.push(i),
+                        Token::Ident(_) if depth == 0 => break,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    let mut columns: HashMap<usize, Vec<Option<String>>> = HashMap::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if let Token::Param(n) = token {
+            columns.entry(*n).or_default().push(owners.get(&i).cloned());
+        }
+    }
+    columns
+}
+
+/// Index of the expression a `::type` cast chain ending at token `i` applies to
+fn skip_casts_back(tokens: &[Token], mut i: usize) -> usize {
+    while i >= 2 && matches!(tokens[i], Token::Ident(_)) && tokens[i - 1] == Token::Op("::".to_string()) {
+        i -= 2;
+    }
+    i
+}
+
+/// Token indices of the placeholders in the operand starting at token
+/// `start`: a placeholder, or a function call or parenthesised list
+fn operand_params(tokens: &[Token], start: usize) -> Vec<usize> {
+    let mut i = start;
+    if matches!(tokens.get(i), Some(Token::Param(_))) {
+        return vec![i];
+    }
+    if matches!(tokens.get(i), Some(Token::Ident(_))) && tokens.get(i + 1) == Some(&Token::Punct('(')) {
+        i += 1;
+    }
+    if tokens.get(i) != Some(&Token::Punct('(')) {
+        return Vec::new();
+    }
+
+    let mut params = Vec::new();
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(i) {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Token::Param(_) => params.push(j),
+            _ => {}
+        }
+    }
+    params
+}
+
+/// Split SQL into identifiers, placeholders, operators and punctuation,
+/// skipping literals and comments
+fn tokenize(query: &str) -> Vec<Token> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '"' => {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && chars[i] != '"' {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()));
+                i += 1;
+            }
+            '$' if next.is_some_and(|n| n.is_ascii_digit()) => {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().collect();
+                if let Ok(n) = digits.parse() {
+                    tokens.push(Token::Param(n));
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()));
+            }
+            c if "=<>!~+-*/%^|&:".contains(c) => {
+                let start = i;
+                while i < chars.len() && "=<>!~+-*/%^|&:".contains(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Op(chars[start..i].iter().collect()));
+            }
+            c if c.is_whitespace() => i += 1,
+            c => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(positions: &[usize], patterns: &[&str]) -> QueryLog {
+        QueryLog {
+            enabled: true,
+            positions: positions.to_vec(),
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_redact_by_position() {
+        let log = log(&[2], &[]);
+        assert_eq!(log.redacted("SELECT $1, $2, $3", 3), vec![false, true, false]);
+    }
+
+    #[test]
+    fn test_redact_by_column_pattern() {
+        let log = log(&[], &["password", "ssn"]);
+        assert_eq!(
+            log.redacted("SELECT * FROM users WHERE email = $1 AND u.password_hash = $2", 2),
+            vec![false, true]
+        );
+        assert_eq!(
+            log.redacted("UPDATE users SET \"SSN\" = $1, name = $2 WHERE id = $3", 3),
+            vec![true, false, false]
+        );
+        assert_eq!(
+            log.redacted("INSERT INTO public.users (id, password, note) VALUES ($1, $2, lower($3)), ($4, $5, $6)", 6),
+            vec![false, true, false, false, true, false]
+        );
+        // Literals and comments are not mistaken for columns
+        assert_eq!(
+            log.redacted("SELECT 'password = $1' -- password = $1\nFROM t WHERE id = $1", 1),
+            vec![false]
+        );
+    }
+
+    #[test]
+    fn test_redact_casts_lists_and_calls() {
+        let log = log(&[], &["password"]);
+        assert_eq!(
+            log.redacted("INSERT INTO users (name, password) VALUES ($1, $2::text)", 2),
+            vec![false, true]
+        );
+        assert_eq!(log.redacted("SELECT * FROM users WHERE password IN ($1) AND id = $2", 2), vec![true, false]);
+        assert_eq!(log.redacted("SELECT 1 FROM users WHERE id NOT IN ($1, $2)", 2), vec![false, false]);
+        assert_eq!(
+            log.redacted("SELECT 1 FROM users WHERE name = $1 AND password = crypt($2, password)", 2),
+            vec![false, true]
+        );
+        assert_eq!(log.redacted("SELECT 1 FROM users u WHERE $1 = u.password AND $2 = id", 2), vec![true, false]);
+        assert_eq!(
+            log.redacted("UPDATE users SET password = $1::text WHERE name::text = $2::text", 2),
+            vec![true, false]
+        );
+        assert_eq!(
+            log.redacted("INSERT INTO users (name, password) VALUES ($1, crypt($2, gen_salt('bf')))", 2),
+            vec![false, true]
+        );
+    }
+
+    #[test]
+    fn test_redact_unattributed_parameters() {
+        // Columns can't be told for these, so with patterns set they're hidden
+        let log = log(&[], &["password"]);
+        assert_eq!(log.redacted("SELECT $1, $2", 2), vec![true, true]);
+        assert_eq!(log.redacted("SELECT * FROM users WHERE id = $1 OR $1 IS NULL", 1), vec![true]);
+        assert_eq!(log.redacted("SELECT * FROM users WHERE id = $1", 2), vec![false, true]);
+        // Positions alone don't hide anything else
+        assert_eq!(self::log(&[2], &[]).redacted("SELECT $1, $2", 2), vec![false, true]);
+    }
+}
@@ -0,0 +1,137 @@
+use pyo3::prelude::*;
+
+/// Keywords `quote_ident` always quotes: every keyword except the unreserved
+/// ones, up to PostgreSQL 17. Sorted for binary search.
+const QUOTED_KEYWORDS: &[&str] = &[
+    "all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric", "authorization",
+    "between", "bigint", "binary", "bit", "boolean", "both", "case", "cast", "char", "character",
+    "check", "coalesce", "collate", "collation", "column", "concurrently", "constraint", "create",
+    "cross", "current_catalog", "current_date", "current_role", "current_schema", "current_time",
+    "current_timestamp", "current_user", "dec", "decimal", "default", "deferrable", "desc",
+    "distinct", "do", "else", "end", "except", "exists", "extract", "false", "fetch", "float",
+    "for", "foreign", "freeze", "from", "full", "grant", "greatest", "group", "grouping", "having",
+    "ilike", "in", "initially", "inner", "inout", "int", "integer", "intersect", "interval",
+    "into", "is", "isnull", "join", "json", "json_array", "json_arrayagg", "json_exists",
+    "json_object", "json_objectagg", "json_query", "json_scalar", "json_serialize", "json_table",
+    "json_value", "lateral", "leading", "least", "left", "like", "limit", "localtime",
+    "localtimestamp", "merge_action", "national", "natural", "nchar", "none", "normalize", "not",
+    "notnull", "null", "nullif", "numeric", "offset", "on", "only", "or", "order", "out", "outer",
+    "overlaps", "overlay", "placing", "position", "precision", "primary", "real", "references",
+    "returning", "right", "row", "select", "session_user", "setof", "similar", "smallint", "some",
+    "substring", "symmetric", "system_user", "table", "tablesample", "then", "time", "timestamp",
+    "to", "trailing", "treat", "trim", "true", "union", "unique", "user", "using", "values",
+    "varchar", "variadic", "verbose", "when", "where", "window", "with", "xmlattributes",
+    "xmlconcat", "xmlelement", "xmlexists", "xmlforest", "xmlnamespaces", "xmlparse", "xmlpi",
+    "xmlroot", "xmlserialize", "xmltable",
+];
+
+/// Quote a name for use as an SQL identifier, following the server's `quote_ident`
+///
+/// Names that are already valid as written (lowercase letters, digits and
+/// underscores, not starting with a digit, not a keyword) are returned as
+/// they are; anything else is wrapped in double quotes with inner double
+/// quotes doubled. None for names PostgreSQL can't represent: empty, or
+/// containing a NUL character.
+pub fn quote_ident(name: &str) -> Option<String> {
+    if name.is_empty() || name.contains('\0') {
+        return None;
+    }
+    let plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && QUOTED_KEYWORDS.binary_search(&name).is_err();
+    if plain {
+        Some(name.to_string())
+    } else {
+        Some(format!("\"{}\"", name.replace('"', "\"\"")))
+    }
+}
+
+/// Quote a string as an SQL literal, following the server's `quote_literal`
+///
+/// Single quotes are doubled; a value containing backslashes becomes an
+/// `E'...'` string with the backslashes doubled, so it reads the same
+/// whatever `standard_conforming_strings` is set to. None for values
+/// containing a NUL character.
+pub fn quote_literal(value: &str) -> Option<String> {
+    if value.contains('\0') {
+        return None;
+    }
+    let escaped = value.replace('\'', "''");
+    if escaped.contains('\\') {
+        Some(format!("E'{}'", escaped.replace('\\', "\\\\")))
+    } else {
+        Some(format!("'{}'", escaped))
+    }
+}
+
+/// Quote the name of a savepoint, cursor or similar object for a statement
+///
+/// Names are case-sensitive, as written. Names PostgreSQL can't represent
+/// raise DataError.
+pub fn object_ident(name: &str) -> PyResult<String> {
+    quote_ident(name).ok_or_else(|| crate::error::type_conversion_error("valid SQL identifier", name))
+}
+
+/// Quote a name for use as an SQL identifier in dynamically built SQL
+///
+/// Mirrors the server's `quote_ident`: `quote_ident("users")` gives `users`,
+/// `quote_ident("Order Items")` gives `"Order Items"`.
+///
+/// Raises:
+///     ValueError: If the name is empty or contains a NUL character
+#[pyfunction]
+#[pyo3(name = "quote_ident")]
+pub fn py_quote_ident(name: &str) -> PyResult<String> {
+    quote_ident(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err("identifiers must be non-empty and can't contain NUL characters")
+    })
+}
+
+/// Quote a value as an SQL literal in dynamically built SQL
+///
+/// Mirrors the server's `quote_literal`: strings are quoted as they are,
+/// other values through `str()`, and None gives `NULL`. Prefer query
+/// parameters wherever the value can be passed as one.
+///
+/// Raises:
+///     ValueError: If the text contains a NUL character
+#[pyfunction]
+#[pyo3(name = "quote_literal")]
+pub fn py_quote_literal(value: &PyAny) -> PyResult<String> {
+    if value.is_none() {
+        return Ok("NULL".to_string());
+    }
+    let text = value.str()?;
+    quote_literal(text.to_str()?).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err("literals can't contain NUL characters")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_ident() {
+        assert!(QUOTED_KEYWORDS.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(quote_ident("users").unwrap(), "users");
+        assert_eq!(quote_ident("_x1").unwrap(), "_x1");
+        assert_eq!(quote_ident("Users").unwrap(), "\"Users\"");
+        assert_eq!(quote_ident("user").unwrap(), "\"user\"");
+        assert_eq!(quote_ident("1st").unwrap(), "\"1st\"");
+        assert_eq!(quote_ident("x$").unwrap(), "\"x$\"");
+        assert_eq!(quote_ident("a\"b").unwrap(), "\"a\"\"b\"");
+        assert_eq!(quote_ident("é").unwrap(), "\"é\"");
+        assert!(quote_ident("").is_none());
+        assert!(quote_ident("a\0b").is_none());
+    }
+
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!(quote_literal("it's").unwrap(), "'it''s'");
+        assert_eq!(quote_literal("a\\b'c").unwrap(), "E'a\\\\b''c'");
+        assert_eq!(quote_literal("").unwrap(), "''");
+        assert!(quote_literal("a\0").is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+/// Result of a statement that returns rows and reports an affected-row count
+/// (e.g. `UPDATE ... RETURNING *`)
+#[pyclass(frozen)]
+pub struct QueryResult {
+    /// Rows returned by the statement
+    #[pyo3(get)]
+    rows: Py<PyList>,
+    /// Number of rows affected, as reported by the server's command tag
+    #[pyo3(get)]
+    rowcount: u64,
+    /// Whether rows were left out because of `max_rows`
+    #[pyo3(get)]
+    truncated: bool,
+}
+
+#[pymethods]
+impl QueryResult {
+    pub fn __len__(&self, py: Python) -> usize {
+        self.rows.as_ref(py).len()
+    }
+}
+
+impl QueryResult {
+    pub fn new(rows: Py<PyList>, rowcount: u64, truncated: bool) -> Self {
+        QueryResult { rows, rowcount, truncated }
+    }
+}
+
+/// Whether the statement is one EXPLAIN accepts: a query or a DML statement
+pub fn is_explainable(query: &str) -> bool {
+    query.trim_start().starts_with('(')
+        || matches!(
+            top_level_words(query).first().map(String::as_str),
+            Some("SELECT" | "WITH" | "VALUES" | "TABLE" | "INSERT" | "UPDATE" | "DELETE" | "MERGE")
+        )
+}
+
+/// Uppercased keywords outside parentheses, literals, quoted identifiers and comments
+fn top_level_words(query: &str) -> Vec<String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut words = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '$' => {
+                // Dollar-quoted body: $tag$ ... $tag$
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                if chars.get(i) == Some(&'$') {
+                    let tag: String = chars[start..=i].iter().collect();
+                    let rest: String = chars[i + 1..].iter().collect();
+                    i = match rest.find(&tag) {
+                        Some(end) => i + 1 + rest[..end].chars().count() + tag.chars().count(),
+                        None => chars.len(),
+                    };
+                }
+            }
+            '(' => {
+                depth += 1;
+                i += 1;
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                if depth == 0 {
+                    words.push(chars[start..i].iter().collect::<String>().to_uppercase());
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_explainable() {
+        assert!(is_explainable("select 1"));
+        assert!(is_explainable("-- totals\nWITH x AS (SELECT 1) SELECT * FROM x"));
+        assert!(is_explainable("(select 1) union (select 2)"));
+        assert!(is_explainable("delete from t where a = 1"));
+        assert!(!is_explainable("create table t (a int)"));
+        assert!(!is_explainable("vacuum t"));
+        assert!(!is_explainable("set search_path = public"));
+    }
+}
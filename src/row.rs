@@ -1,72 +1,516 @@
-use crate::types::postgres_to_py;
+use crate::error::{map_db_error, no_data_error, too_many_rows_error};
+use crate::runtime::RuntimeManager;
+use crate::types::{postgres_to_py, ConversionOptions};
+use compact_str::CompactString;
 use pyo3::prelude::*;
+use pyo3::pyclass::CompareOp;
+use pyo3::types::{PyDict, PyIterator, PyList, PyLong, PySlice, PyString, PyTuple};
 use smallvec::SmallVec;
-use tokio_postgres::Row as TokioRow;
+use std::collections::VecDeque;
+use tokio::sync::mpsc::Receiver;
+use futures_util::TryStreamExt;
+use tokio_postgres::{Client, Column, Row as TokioRow, RowStream, ToStatement};
+use tokio_postgres::SimpleQueryRow;
+
+/// Run a query that must return exactly one row and return that row
+///
+/// Reads at most two rows, so a query that unexpectedly matches many isn't
+/// fetched in full. No rows raises NoDataError and more than one raises
+/// TooManyRowsError, where `Client::query_one` reports both the same way.
+pub async fn query_single<T: ?Sized + ToStatement>(
+    client: &Client,
+    statement: &T,
+    params: &[&(dyn postgres_types::ToSql + Sync)],
+) -> PyResult<TokioRow> {
+    let stream = client.query_raw(statement, params.iter().copied()).await.map_err(map_db_error)?;
+    single_row(stream).await
+}
+
+/// The only row of a result stream, with the same checks as `query_single`
+pub async fn single_row(stream: RowStream) -> PyResult<TokioRow> {
+    tokio::pin!(stream);
+
+    let row = stream.try_next().await.map_err(map_db_error)?.ok_or_else(no_data_error)?;
+    if stream.try_next().await.map_err(map_db_error)?.is_some() {
+        return Err(too_many_rows_error());
+    }
+    Ok(row)
+}
+
+/// The first row of a result stream, if any; the rest is never read
+pub async fn first_row(stream: RowStream) -> PyResult<Option<TokioRow>> {
+    tokio::pin!(stream);
+    stream.try_next().await.map_err(map_db_error)
+}
 
 /// High-performance immutable row with pre-allocated storage
 #[pyclass(frozen)]
 pub struct Row {
     data: SmallVec<[PyObject; 16]>, // Stack allocation for ≤16 columns (common case)
+    columns: SmallVec<[CompactString; 16]>, // Column names, parallel to `data`
+    origins: SmallVec<[(u32, i16); 16]>, // Source table OID and attribute number, 0 if not a table column
 }
 
 #[pymethods]
 impl Row {
     pub fn __getitem__(&self, py: Python, key: &PyAny) -> PyResult<PyObject> {
-        if let Ok(idx) = key.extract::<usize>() {
-            // Access by index
-            if idx < self.data.len() {
-                Ok(self.data[idx].clone_ref(py))
-            } else {
-                Err(pyo3::exceptions::PyIndexError::new_err(
+        if key.is_instance_of::<PySlice>() {
+            // A tuple of the selected values, sliced as a tuple would be
+            Ok(self.values_tuple(py).as_ref().get_item(key)?.into())
+        } else if key.is_instance_of::<PyLong>() {
+            // Access by index, negative counting from the end
+            match self.position(key) {
+                Some(idx) => Ok(self.data[idx].clone_ref(py)),
+                None => Err(pyo3::exceptions::PyIndexError::new_err(
                     "Index out of range",
-                ))
+                )),
             }
         } else if let Ok(col_name) = key.extract::<&str>() {
-            // Access by column name - for now just return the first column
-            // TODO: Store column names mapping for proper column name access
-            if !self.data.is_empty() {
-                Ok(self.data[0].clone_ref(py))
-            } else {
-                Err(pyo3::exceptions::PyKeyError::new_err(format!(
+            // Access by column name
+            match column_index(&self.columns, col_name) {
+                Some(idx) => Ok(self.data[idx].clone_ref(py)),
+                None => Err(pyo3::exceptions::PyKeyError::new_err(format!(
                     "Column '{}' not found",
                     col_name
-                )))
+                ))),
             }
         } else {
             Err(pyo3::exceptions::PyTypeError::new_err(
-                "Key must be integer or string",
+                "Key must be integer, string or slice",
             ))
         }
     }
 
+    /// Return the value for a column name or index, or `default` if the row
+    /// has no such column
+    ///
+    /// A NULL column is present, so it returns None rather than `default`.
+    /// Negative indexes count from the end, as with `row[-1]`.
+    #[pyo3(signature = (key, default=None))]
+    pub fn get(&self, py: Python, key: &PyAny, default: Option<PyObject>) -> PyResult<PyObject> {
+        let idx = if key.is_instance_of::<PyLong>() {
+            self.position(key)
+        } else if let Ok(col_name) = key.extract::<&str>() {
+            column_index(&self.columns, col_name)
+        } else {
+            return Err(pyo3::exceptions::PyTypeError::new_err(
+                "Key must be integer or string",
+            ));
+        };
+        match idx {
+            Some(idx) => Ok(self.data[idx].clone_ref(py)),
+            None => Ok(default.unwrap_or_else(|| py.None())),
+        }
+    }
+
     pub fn __len__(&self) -> usize {
         self.data.len()
     }
+
+    /// Iterate over the values in column order
+    pub fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        PyIterator::from_object(self.values_tuple(py))
+    }
+
+    /// `x in row` is true if `x` equals one of the values, as for a tuple.
+    /// Column names aren't matched; check those with `name in row.keys()`.
+    pub fn __contains__(&self, py: Python, item: &PyAny) -> PyResult<bool> {
+        for value in &self.data {
+            if value.as_ref(py).eq(item)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Return a dict mapping column names to values
+    ///
+    /// When several columns share a name (e.g. `id` from both sides of a
+    /// join), the last one wins, as with psycopg2's `DictRow`. Use index
+    /// access or `items()` to reach the others.
+    pub fn as_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        for (name, value) in self.columns.iter().zip(&self.data) {
+            dict.set_item(name.as_str(), value.clone_ref(py))?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Alias of `as_dict`
+    pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        self.as_dict(py)
+    }
+
+    /// Return the column names in column order
+    pub fn keys(&self) -> Vec<&str> {
+        self.columns.iter().map(CompactString::as_str).collect()
+    }
+
+    /// Return the column values in column order
+    pub fn values(&self, py: Python) -> Vec<PyObject> {
+        self.data.iter().map(|value| value.clone_ref(py)).collect()
+    }
+
+    /// Return the table column each result column was read from
+    ///
+    /// One entry per column, in column order: a `(table_oid, position)`
+    /// tuple, where `position` is the column's attribute number in that
+    /// table, or None for computed columns and text-format results. Resolve
+    /// the OID to a table name with `SELECT <oid>::regclass`.
+    pub fn column_origins(&self) -> Vec<Option<(u32, i16)>> {
+        self.origins
+            .iter()
+            .map(|&(table_oid, position)| if table_oid == 0 { None } else { Some((table_oid, position)) })
+            .collect()
+    }
+
+    /// Return `(name, value)` pairs in column order, duplicates included
+    pub fn items(&self, py: Python) -> Vec<(&str, PyObject)> {
+        self.columns
+            .iter()
+            .zip(&self.data)
+            .map(|(name, value)| (name.as_str(), value.clone_ref(py)))
+            .collect()
+    }
+
+    /// `Row(id=1, name='alice')`, with each value's own repr
+    pub fn __repr__(&self, py: Python) -> PyResult<String> {
+        let mut fields = Vec::with_capacity(self.data.len());
+        for (name, value) in self.columns.iter().zip(&self.data) {
+            fields.push(format!("{}={}", name, value.as_ref(py).repr()?));
+        }
+        Ok(format!("Row({})", fields.join(", ")))
+    }
+
+    /// Rows are equal when their column names and values are; a row also
+    /// equals a tuple or list of the same values, for comparing against
+    /// expected results in tests
+    pub fn __richcmp__(&self, py: Python, other: &PyAny, op: CompareOp) -> PyResult<PyObject> {
+        let equal = if let Ok(other) = other.downcast::<PyCell<Row>>() {
+            let other = other.get();
+            self.columns == other.columns && self.values_tuple(py).eq(other.values_tuple(py))?
+        } else if other.is_instance_of::<PyTuple>() {
+            self.values_tuple(py).eq(other)?
+        } else if let Ok(list) = other.downcast::<PyList>() {
+            self.values_tuple(py).eq(list.to_tuple())?
+        } else {
+            return Ok(py.NotImplemented());
+        };
+        match op {
+            CompareOp::Eq => Ok(equal.into_py(py)),
+            CompareOp::Ne => Ok((!equal).into_py(py)),
+            _ => Ok(py.NotImplemented()),
+        }
+    }
+
+    /// Hash of the values, matching the tuple a row compares equal to.
+    /// Raises TypeError if a value is unhashable, such as an array or JSON
+    /// column.
+    pub fn __hash__(&self, py: Python) -> PyResult<isize> {
+        self.values_tuple(py).hash()
+    }
 }
 
 impl Row {
+    fn values_tuple<'py>(&self, py: Python<'py>) -> &'py PyTuple {
+        PyTuple::new(py, self.data.iter())
+    }
+
+    fn position(&self, index: &PyAny) -> Option<usize> {
+        index_position(index, self.data.len())
+    }
+
     /// High-performance row conversion with pre-allocation
-    pub fn from_tokio_row(py: Python, row: &TokioRow) -> PyResult<Self> {
+    pub fn from_tokio_row(py: Python, row: &TokioRow, options: ConversionOptions) -> PyResult<Self> {
         let column_count = row.len();
         let mut data = SmallVec::with_capacity(column_count);
+        let mut columns = SmallVec::with_capacity(column_count);
+        let mut origins = SmallVec::with_capacity(column_count);
 
         // Bulk process columns for better cache locality
-        for i in 0..column_count {
-            let col_type = row.columns()[i].type_();
-            data.push(postgres_to_py(py, row, i, col_type)?);
+        for (i, column) in row.columns().iter().enumerate() {
+            data.push(postgres_to_py(py, row, i, column.type_(), options)?);
+            columns.push(CompactString::new(column.name()));
+            origins.push((column.table_oid().unwrap_or(0), column.column_id().unwrap_or(0)));
         }
 
-        Ok(Row { data })
+        Ok(Row { data, columns, origins })
+    }
+
+    /// Build a row from a simple query result, keeping every value as text
+    pub fn from_simple_row(py: Python, row: &SimpleQueryRow) -> Self {
+        let data = (0..row.len())
+            .map(|i| row.get(i).into_py(py))
+            .collect();
+        let columns = row
+            .columns()
+            .iter()
+            .map(|column| CompactString::new(column.name()))
+            .collect();
+        let origins = smallvec::smallvec![(0, 0); row.len()];
+
+        Row { data, columns, origins }
     }
 
     /// Bulk create multiple rows with optimized processing
-    pub fn from_tokio_rows(py: Python, rows: &[TokioRow]) -> PyResult<Vec<Self>> {
+    pub fn from_tokio_rows(py: Python, rows: &[TokioRow], options: ConversionOptions) -> PyResult<Vec<Self>> {
         let mut result = Vec::with_capacity(rows.len());
 
         for row in rows {
-            result.push(Self::from_tokio_row(py, row)?);
+            result.push(Self::from_tokio_row(py, row, options)?);
         }
 
         Ok(result)
     }
 }
+
+/// PEP 249 `description` for a result's columns
+///
+/// One `(name, type_code, display_size, internal_size, precision, scale,
+/// null_ok)` tuple per column, where `type_code` is the type OID. The row
+/// description the server sends has no sizes, precision or nullability, so
+/// those fields are None. A statement with no result columns, such as an
+/// UPDATE, has no description (None), as PEP 249 specifies.
+pub fn description(py: Python, columns: &[Column]) -> Option<Py<PyList>> {
+    if columns.is_empty() {
+        return None;
+    }
+    let none: Option<i32> = None;
+    let entries = columns.iter().map(|column| -> PyObject {
+        (column.name(), column.type_().oid(), none, none, none, none, none).into_py(py)
+    });
+    Some(PyList::new(py, entries).into())
+}
+
+/// Convert rows straight into a list of dicts, without building Row objects
+///
+/// Each column name becomes one Python string shared by every dict. When
+/// several columns share a name, the last one wins, as with `Row.as_dict`.
+pub fn dicts_from_tokio_rows(py: Python, rows: &[TokioRow], options: ConversionOptions) -> PyResult<Py<PyList>> {
+    let list = PyList::empty(py);
+    let columns = match rows.first() {
+        Some(row) => row.columns(),
+        None => return Ok(list.into()),
+    };
+    let names: Vec<&PyString> = columns.iter().map(|column| PyString::new(py, column.name())).collect();
+
+    for row in rows {
+        let dict = PyDict::new(py);
+        for (i, (name, column)) in names.iter().zip(columns).enumerate() {
+            dict.set_item(*name, postgres_to_py(py, row, i, column.type_(), options)?)?;
+        }
+        list.append(dict)?;
+    }
+    Ok(list.into())
+}
+
+/// Position of an int index among `len` columns, counting from the end when
+/// negative as a tuple would; None when out of range
+pub fn index_position(index: &PyAny, len: usize) -> Option<usize> {
+    // Too large for isize is out of range too
+    let index = index.extract::<isize>().ok()?;
+    let position = if index < 0 { index.checked_add(len as isize)? } else { index };
+    usize::try_from(position).ok().filter(|&position| position < len)
+}
+
+/// Index of the first column with the given name
+fn column_index(columns: &[CompactString], name: &str) -> Option<usize> {
+    columns.iter().position(|column| column.as_str() == name)
+}
+
+// Most rows taken from the stream per GIL release, and most buffered ahead
+pub const FETCH_BATCH: usize = 1000;
+
+/// Iterator that decodes every row into the same list object
+///
+/// Each `__next__` overwrites the list returned by the previous call, so
+/// rows must be consumed before advancing and never kept. Rows stream in
+/// from a background task as the server sends them; when none are buffered,
+/// `__next__` waits for the next batch with the GIL released so other
+/// Python threads keep running during the network wait.
+#[pyclass(name = "ReusedRowIterator")]
+pub struct ReusedRowIterator {
+    rows: VecDeque<TokioRow>,
+    // Raised once the rows received before it have been yielded
+    error: Option<PyErr>,
+    receiver: Receiver<PyResult<TokioRow>>,
+    runtime: RuntimeManager,
+    buffer: Option<Py<PyList>>,
+    options: ConversionOptions,
+}
+
+#[pymethods]
+impl ReusedRowIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyList>>> {
+        if self.rows.is_empty() && self.error.is_none() {
+            self.fetch_batch(py);
+        }
+        let row = match self.rows.pop_front() {
+            Some(row) => row,
+            None => return self.error.take().map_or(Ok(None), Err),
+        };
+
+        let buffer = self
+            .buffer
+            .get_or_insert_with(|| PyList::new(py, vec![py.None(); row.len()]).into());
+        let list = buffer.as_ref(py);
+        for (i, column) in row.columns().iter().enumerate() {
+            list.set_item(i, postgres_to_py(py, &row, i, column.type_(), self.options)?)?;
+        }
+
+        Ok(Some(buffer.clone_ref(py)))
+    }
+}
+
+impl ReusedRowIterator {
+    pub fn new(
+        receiver: Receiver<PyResult<TokioRow>>,
+        runtime: RuntimeManager,
+        options: ConversionOptions,
+    ) -> Self {
+        ReusedRowIterator {
+            rows: VecDeque::new(),
+            error: None,
+            receiver,
+            runtime,
+            buffer: None,
+            options,
+        }
+    }
+
+    /// Wait for the next rows without holding the GIL; leaves `rows` empty
+    /// once the stream is exhausted
+    fn fetch_batch(&mut self, py: Python) {
+        let (receiver, runtime) = (&mut self.receiver, &self.runtime);
+        let batch = py.allow_threads(move || {
+            runtime.block_on(async move {
+                let mut batch = Vec::with_capacity(FETCH_BATCH);
+                receiver.recv_many(&mut batch, FETCH_BATCH).await;
+                batch
+            })
+        });
+
+        for row in batch {
+            match row {
+                Ok(row) => self.rows.push_back(row),
+                // The stream ends after an error
+                Err(e) => {
+                    self.error = Some(e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::IntoPyDict;
+
+    #[test]
+    fn test_column_index() {
+        let columns: Vec<CompactString> = ["id", "email", "id"].iter().map(CompactString::new).collect();
+
+        assert_eq!(column_index(&columns, "email"), Some(1));
+        // Duplicate names resolve to the first match
+        assert_eq!(column_index(&columns, "id"), Some(0));
+        assert_eq!(column_index(&columns, "missing"), None);
+    }
+
+    #[test]
+    fn test_column_index_past_inline_capacity() {
+        // 20 columns spill the SmallVec onto the heap
+        let columns: SmallVec<[CompactString; 16]> =
+            (0..20).map(|i| CompactString::new(format!("c{}", i))).collect();
+        assert!(columns.spilled());
+
+        let found: Vec<_> = (0..20).map(|i| column_index(&columns, &format!("c{}", i))).collect();
+        assert_eq!(found, (0..20).map(Some).collect::<Vec<_>>());
+    }
+
+    /// A row of `values` under `names`, as if read from a query
+    fn row<'py>(py: Python<'py>, names: &[&str], values: Vec<PyObject>) -> &'py PyCell<Row> {
+        let row = Row {
+            data: values.into_iter().collect(),
+            columns: names.iter().map(CompactString::new).collect(),
+            origins: smallvec::smallvec![(0, 0); names.len()],
+        };
+        PyCell::new(py, row).unwrap()
+    }
+
+    #[test]
+    fn test_get_tells_null_from_missing() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let row = row(py, &["id", "note"], vec![7.into_py(py), py.None()]);
+            let get = |key: PyObject| -> PyObject { row.call_method1("get", (key, "default")).unwrap().into() };
+
+            // A NULL column is present, so its None comes back
+            assert!(get("note".into_py(py)).is_none(py));
+            assert!(get(1.into_py(py)).is_none(py));
+            assert!(row.call_method1("get", ("missing",)).unwrap().is_none());
+
+            assert_eq!(get("id".into_py(py)).extract::<i64>(py).unwrap(), 7);
+            assert_eq!(get(0.into_py(py)).extract::<i64>(py).unwrap(), 7);
+            assert_eq!(get("missing".into_py(py)).extract::<String>(py).unwrap(), "default");
+            assert_eq!(get(2.into_py(py)).extract::<String>(py).unwrap(), "default");
+            assert_eq!(get((-2).into_py(py)).extract::<i64>(py).unwrap(), 7);
+            assert!(get((-1).into_py(py)).is_none(py));
+            assert_eq!(get((-3).into_py(py)).extract::<String>(py).unwrap(), "default");
+            assert_eq!(get((1u128 << 70).into_py(py)).extract::<String>(py).unwrap(), "default");
+
+            assert!(row.call_method1("get", (1.5,)).unwrap_err().is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_getitem_indexes_and_slices_like_a_tuple() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let row = row(py, &["a", "b", "c", "d"], (1..=4).map(|i| i.into_py(py)).collect());
+            let locals = [("row", row)].into_py_dict(py);
+            let eval = |expr: &str| py.eval(expr, None, Some(locals));
+
+            assert_eq!(eval("row[-1]").unwrap().extract::<i64>().unwrap(), 4);
+            assert_eq!(eval("row[-4]").unwrap().extract::<i64>().unwrap(), 1);
+            assert_eq!(eval("row[::-1]").unwrap().extract::<Vec<i64>>().unwrap(), vec![4, 3, 2, 1]);
+            assert_eq!(eval("row[1:10:2]").unwrap().extract::<Vec<i64>>().unwrap(), vec![2, 4]);
+            assert_eq!(eval("row[-2:]").unwrap().extract::<Vec<i64>>().unwrap(), vec![3, 4]);
+            assert!(eval("row[1:3]").unwrap().is_instance_of::<PyTuple>());
+            assert_eq!(eval("row['c']").unwrap().extract::<i64>().unwrap(), 3);
+
+            for expr in ["row[4]", "row[-5]", "row[2 ** 70]", "row[-2 ** 70]"] {
+                assert!(eval(expr).unwrap_err().is_instance_of::<pyo3::exceptions::PyIndexError>(py), "{}", expr);
+            }
+            assert!(eval("row['e']").unwrap_err().is_instance_of::<pyo3::exceptions::PyKeyError>(py));
+            assert!(eval("row[1.0]").unwrap_err().is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_iterate_row_past_inline_capacity() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let row = Row {
+                data: (0..20).map(|i| (i * 10).into_py(py)).collect(),
+                columns: (0..20).map(|i| CompactString::new(format!("c{}", i))).collect(),
+                origins: smallvec::smallvec![(0, 0); 20],
+            };
+            assert!(row.data.spilled());
+            let row = PyCell::new(py, row).unwrap();
+
+            let values: Vec<i64> = row.iter().unwrap().map(|value| value.unwrap().extract().unwrap()).collect();
+            assert_eq!(values, (0..20).map(|i| i * 10).collect::<Vec<_>>());
+
+            // Membership is by value, as for a tuple; names don't count
+            assert!(row.contains(190).unwrap());
+            assert!(!row.contains(200).unwrap());
+            assert!(!row.contains("c19").unwrap());
+        });
+    }
+}
@@ -1,6 +1,8 @@
 use once_cell::sync::Lazy;
+use pyo3::Python;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
 
 static RUNTIME: Lazy<Arc<Runtime>> = Lazy::new(|| {
     Arc::new(
@@ -28,10 +30,20 @@ impl RuntimeManager {
         self.runtime.block_on(future)
     }
 
-    pub fn spawn<F>(&self, future: F)
+    /// Like `block_on`, but with the GIL released while the future runs, so
+    /// other Python threads keep running, and can cancel the statement
+    pub fn block_on_without_gil<F>(&self, py: Python, future: F) -> F::Output
+    where
+        F: std::future::Future + Send,
+        F::Output: Send,
+    {
+        py.allow_threads(|| self.runtime.block_on(future))
+    }
+
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<()>
     where
         F: std::future::Future<Output = ()> + Send + 'static,
     {
-        self.runtime.spawn(future);
+        self.runtime.spawn(future)
     }
 }
\ No newline at end of file
@@ -0,0 +1,95 @@
+use parking_lot::Mutex;
+use pyo3::PyResult;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{connection_closed_error, connection_lost_error};
+
+/// Whether a connection is closed, and why if it failed, shared with the
+/// prepared statements and cursors that run on its client
+///
+/// The background task marks a failure without waiting on a lock the
+/// statement side may hold, so a dropped connection is always noticed.
+#[derive(Default)]
+pub struct ConnectionState {
+    closed: AtomicBool,
+    // Closed by its owner, after a failure or not
+    closed_by_owner: AtomicBool,
+    // Error that ended the connection, if it failed rather than being closed
+    failure: Mutex<Option<String>>,
+}
+
+impl ConnectionState {
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Mark the connection closed by its owner
+    pub fn close(&self) {
+        self.closed_by_owner.store(true, Ordering::SeqCst);
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    /// Record the error that ended the connection and mark it closed
+    pub fn fail(&self, reason: String) {
+        // Set before the flag, so a closed state always shows its cause
+        *self.failure.lock() = Some(reason);
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    /// The error that ended the connection, if it failed
+    pub fn failure(&self) -> Option<String> {
+        self.failure.lock().clone()
+    }
+
+    /// The error that ended the connection, unless its owner has closed it
+    /// since, in which case it isn't to be reopened
+    pub fn lost(&self) -> Option<String> {
+        if self.closed_by_owner.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.failure()
+    }
+
+    /// Raise if the connection can't be used: OperationalError with the
+    /// cause if it failed, InterfaceError if it was closed
+    pub fn check_open(&self) -> PyResult<()> {
+        if !self.is_closed() {
+            return Ok(());
+        }
+        match self.failure() {
+            Some(reason) => Err(connection_lost_error(&reason)),
+            None => Err(connection_closed_error()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_closes() {
+        let state = ConnectionState::default();
+        assert!(!state.is_closed());
+        assert_eq!(state.failure(), None);
+
+        state.fail("connection reset".to_string());
+        assert!(state.is_closed());
+        assert_eq!(state.failure().as_deref(), Some("connection reset"));
+        assert_eq!(state.lost().as_deref(), Some("connection reset"));
+
+        // Closing keeps the cause but the connection is no longer lost
+        state.close();
+        assert_eq!(state.failure().as_deref(), Some("connection reset"));
+        assert_eq!(state.lost(), None);
+    }
+
+    #[test]
+    fn test_close_has_no_failure() {
+        let state = ConnectionState::default();
+        state.close();
+        assert!(state.is_closed());
+        assert_eq!(state.failure(), None);
+        assert_eq!(state.lost(), None);
+    }
+}
@@ -0,0 +1,195 @@
+use pyo3::prelude::*;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, Statement};
+
+use crate::connection::PgConnection;
+use crate::error::map_db_error;
+use crate::paramstyle::{bind_translated, Placeholders};
+use crate::row::{query_single, Row};
+use crate::querylog::QueryLog;
+use crate::state::ConnectionState;
+use crate::types::{py_objects_to_postgres_values, ConversionOptions};
+
+/// Handle to a statement prepared on a connection
+///
+/// Holds the server-side statement directly, so it stays usable even after
+/// being evicted from the connection's statement cache. The server-side
+/// statement is released once the handle and all cache entries are dropped.
+/// Statements run like the connection's own: with autocommit off inside its
+/// transaction, and within its statement time limit.
+#[pyclass(name = "PreparedStatement")]
+pub struct PreparedStatement {
+    connection: Py<PgConnection>,
+    client: Arc<Mutex<Client>>,
+    state: Arc<ConnectionState>,
+    statement: Statement,
+    conversion: ConversionOptions,
+    query_log: Arc<QueryLog>,
+    // Pyformat placeholders the SQL was translated from, if any
+    placeholders: Option<Placeholders>,
+    /// SQL text of the statement, with `$N` placeholders
+    #[pyo3(get)]
+    sql: String,
+}
+
+#[pymethods]
+impl PreparedStatement {
+    /// Parameter type names inferred by the server, in placeholder order
+    #[getter]
+    pub fn param_types(&self) -> Vec<String> {
+        self.statement.params().iter().map(|t| t.name().to_string()).collect()
+    }
+
+    /// Execute the statement without returning rows
+    ///
+    /// Args:
+    ///     params: Query parameters (optional)
+    ///     statement_timeout_ms: Time limit for this statement, overriding
+    ///         the connection's; 0 for no limit (optional)
+    ///
+    /// Returns:
+    ///     int: Number of rows affected
+    #[pyo3(signature = (params=None, statement_timeout_ms=None))]
+    pub fn execute(&self, py: Python, params: Option<&PyAny>, statement_timeout_ms: Option<u64>) -> PyResult<u64> {
+        let connection = self.connection.borrow(py);
+        self.begin_statement(&connection)?;
+        let params = bind_translated(self.placeholders.as_ref(), py, params)?;
+        self.query_log.log(py, &self.sql, params)?;
+
+        let postgres_params = if let Some(p) = params {
+            let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
+            py_objects_to_postgres_values(py, &params_vec, self.conversion)?
+        } else {
+            Vec::new()
+        };
+
+        let client = Arc::clone(&self.client);
+        let statement = self.statement.clone();
+        connection.run_statement(py, statement_timeout_ms, async move {
+            let client = client.lock().await;
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+
+            client.execute(&statement, &params_refs[..]).await.map_err(map_db_error)
+        })
+    }
+
+    /// Execute the statement and return all rows
+    ///
+    /// Args:
+    ///     params: Query parameters (optional)
+    ///     statement_timeout_ms: Time limit for this statement, overriding
+    ///         the connection's; 0 for no limit (optional)
+    ///
+    /// Returns:
+    ///     list: List of Row objects
+    #[pyo3(signature = (params=None, statement_timeout_ms=None))]
+    pub fn query(&self, py: Python, params: Option<&PyAny>, statement_timeout_ms: Option<u64>) -> PyResult<PyObject> {
+        let connection = self.connection.borrow(py);
+        self.begin_statement(&connection)?;
+        let params = bind_translated(self.placeholders.as_ref(), py, params)?;
+        self.query_log.log(py, &self.sql, params)?;
+
+        let postgres_params = if let Some(p) = params {
+            let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
+            py_objects_to_postgres_values(py, &params_vec, self.conversion)?
+        } else {
+            Vec::new()
+        };
+
+        let client = Arc::clone(&self.client);
+        let statement = self.statement.clone();
+        let rows = connection.run_statement(py, statement_timeout_ms, async move {
+            let client = client.lock().await;
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+
+            client.query(&statement, &params_refs[..]).await.map_err(map_db_error)
+        })?;
+
+        Ok(Row::from_tokio_rows(py, &rows, self.conversion)?.into_py(py))
+    }
+
+    /// Execute the statement and return exactly one row
+    ///
+    /// Args:
+    ///     params: Query parameters (optional)
+    ///     statement_timeout_ms: Time limit for this statement, overriding
+    ///         the connection's; 0 for no limit (optional)
+    ///
+    /// Returns:
+    ///     Row: Single row result
+    ///
+    /// Raises:
+    ///     NoDataError: If the query returned no rows
+    ///     TooManyRowsError: If the query returned more than one row
+    #[pyo3(signature = (params=None, statement_timeout_ms=None))]
+    pub fn query_one(&self, py: Python, params: Option<&PyAny>, statement_timeout_ms: Option<u64>) -> PyResult<Py<Row>> {
+        let connection = self.connection.borrow(py);
+        self.begin_statement(&connection)?;
+        let params = bind_translated(self.placeholders.as_ref(), py, params)?;
+        self.query_log.log(py, &self.sql, params)?;
+
+        let postgres_params = if let Some(p) = params {
+            let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
+            py_objects_to_postgres_values(py, &params_vec, self.conversion)?
+        } else {
+            Vec::new()
+        };
+
+        let client = Arc::clone(&self.client);
+        let statement = self.statement.clone();
+        let row = connection.run_statement(py, statement_timeout_ms, async move {
+            let client = client.lock().await;
+            let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
+                .collect();
+
+            query_single(&client, &statement, &params_refs[..]).await
+        })?;
+
+        Py::new(py, Row::from_tokio_row(py, &row, self.conversion)?)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("PreparedStatement({:?})", self.sql)
+    }
+}
+
+impl PreparedStatement {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        connection: Py<PgConnection>,
+        client: Arc<Mutex<Client>>,
+        state: Arc<ConnectionState>,
+        statement: Statement,
+        conversion: ConversionOptions,
+        query_log: Arc<QueryLog>,
+        placeholders: Option<Placeholders>,
+        sql: String,
+    ) -> Self {
+        Self {
+            connection,
+            client,
+            state,
+            statement,
+            conversion,
+            query_log,
+            placeholders,
+            sql,
+        }
+    }
+
+    /// Check the owning connection as its own statements do, then that it's
+    /// still on the session this statement was prepared on
+    fn begin_statement(&self, connection: &PgConnection) -> PyResult<()> {
+        connection.begin_statement()?;
+        self.state.check_open()
+    }
+}
@@ -0,0 +1,41 @@
+//! Helpers for the tests that need a PostgreSQL server
+//!
+//! Those tests are marked `#[ignore]`, so a plain `cargo test` lists them as
+//! ignored rather than passing without checking anything. To run them, set
+//! POSTPYRO_TEST_DSN to the server's connection string, e.g.
+//! postgresql://postgres@localhost/postgres, and pass `--include-ignored`:
+//!
+//! ```text
+//! POSTPYRO_TEST_DSN=postgresql://postgres@localhost/postgres cargo test -- --include-ignored
+//! ```
+
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+
+use crate::connection::PgConnection;
+
+/// The connection string from POSTPYRO_TEST_DSN
+pub fn test_dsn() -> String {
+    std::env::var("POSTPYRO_TEST_DSN").expect("set POSTPYRO_TEST_DSN to run the tests that need a server")
+}
+
+/// Run `f` with the GIL held, initializing the interpreter first
+pub fn with_gil<R>(f: impl FnOnce(Python) -> R) -> R {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(f)
+}
+
+/// Connect to the test server with the given keyword arguments
+pub fn connect<'py>(py: Python<'py>, kwargs: &[(&str, PyObject)]) -> &'py PyCell<PgConnection> {
+    let kwargs = kwargs.into_py_dict(py);
+    let connection = py.get_type::<PgConnection>().call((test_dsn(),), Some(kwargs)).unwrap();
+    connection.downcast().unwrap()
+}
+
+/// Run Python statements with the given names bound as globals, so that
+/// functions defined in `code` can see them too
+pub fn run(py: Python, globals: impl IntoPyDict, code: &str) {
+    if let Err(e) = py.run(code, Some(globals.into_py_dict(py)), None) {
+        panic!("{}", e.value(py));
+    }
+}
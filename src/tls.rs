@@ -0,0 +1,336 @@
+use std::sync::Arc;
+use pyo3::prelude::*;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use tokio_postgres::config::SslMode as PgSslMode;
+use tokio_postgres::Config;
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+use crate::error::{invalid_connection_string_error, tls_config_error};
+
+/// Connection string options handled here rather than by tokio-postgres,
+/// which only knows sslmode disable/prefer/require and no certificate files
+const TLS_OPTIONS: [&str; 4] = ["sslmode", "sslrootcert", "sslcert", "sslkey"];
+
+/// How a connection uses TLS, as libpq's `sslmode`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SslMode {
+    /// Never use TLS
+    Disable,
+    /// Use TLS if the server supports it, without verifying its certificate
+    Prefer,
+    /// Require TLS, without verifying the server certificate
+    Require,
+    /// Require TLS and a certificate for the host, signed by a trusted root
+    VerifyFull,
+}
+
+impl SslMode {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "disable" => Ok(Self::Disable),
+            "prefer" => Ok(Self::Prefer),
+            "require" => Ok(Self::Require),
+            "verify-full" => Ok(Self::VerifyFull),
+            _ => Err(invalid_connection_string_error(&format!(
+                "sslmode must be 'disable', 'prefer', 'require' or 'verify-full', got '{}'",
+                name
+            ))),
+        }
+    }
+}
+
+/// TLS settings taken from a connection string
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsOptions {
+    pub mode: SslMode,
+    /// PEM file with the root certificates to trust, or "system"
+    pub root_cert: Option<String>,
+    /// PEM files with the client certificate (chain) and its private key,
+    /// for servers that require client certificate authentication
+    pub cert: Option<String>,
+    pub key: Option<String>,
+}
+
+impl TlsOptions {
+    /// Parse a connection string, taking out the TLS options
+    ///
+    /// Accepts URLs and key=value strings. The remaining string is returned
+    /// for tokio-postgres to parse.
+    pub fn split(connection_string: &str) -> PyResult<(String, Self)> {
+        let (rest, options) = extract_options(connection_string, &TLS_OPTIONS);
+        let mut tls = Self { mode: SslMode::Prefer, root_cert: None, cert: None, key: None };
+        for (key, value) in options {
+            match key.as_str() {
+                "sslmode" => tls.mode = SslMode::from_name(&value)?,
+                "sslrootcert" => tls.root_cert = Some(value),
+                "sslcert" => tls.cert = Some(value),
+                _ => tls.key = Some(value),
+            }
+        }
+        Ok((rest, tls))
+    }
+
+    /// Use the given client certificate and key files over those in the
+    /// connection string
+    pub fn set_client_cert(&mut self, cert: Option<&str>, key: Option<&str>) {
+        if let Some(cert) = cert {
+            self.cert = Some(cert.to_string());
+        }
+        if let Some(key) = key {
+            self.key = Some(key.to_string());
+        }
+    }
+
+    /// Set the parsed config's sslmode to match
+    pub fn apply(&self, config: &mut Config) {
+        config.ssl_mode(match self.mode {
+            SslMode::Disable => PgSslMode::Disable,
+            SslMode::Prefer => PgSslMode::Prefer,
+            SslMode::Require | SslMode::VerifyFull => PgSslMode::Require,
+        });
+    }
+
+    /// Build the TLS connector for these settings
+    ///
+    /// Only verify-full checks the server certificate, against the roots in
+    /// `sslrootcert`, or the system's trusted roots if it is unset or
+    /// "system". Other modes encrypt without verifying, like libpq. A client
+    /// certificate, if set, is presented in every mode.
+    pub fn connector(&self) -> PyResult<MakeRustlsConnect> {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| tls_config_error(&format!("TLS setup failed: {}", e)))?;
+
+        let builder = if self.mode == SslMode::VerifyFull {
+            builder.with_root_certificates(self.root_store()?)
+        } else {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(NoCertificateVerification(provider)))
+        };
+        let config = match self.client_identity()? {
+            Some((certs, key)) => builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| tls_config_error(&format!("Invalid client certificate or key: {}", e)))?,
+            None => builder.with_no_client_auth(),
+        };
+        Ok(MakeRustlsConnect::new(config))
+    }
+
+    /// Load the client certificate chain and private key, if configured
+    fn client_identity(&self) -> PyResult<Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>> {
+        let (cert, key) = match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => (cert, key),
+            (None, None) => return Ok(None),
+            _ => return Err(tls_config_error("sslcert and sslkey must be given together")),
+        };
+        let certs = read_certificates(cert, "sslcert")?;
+        let key = PrivateKeyDer::from_pem_file(key)
+            .map_err(|e| tls_config_error(&format!("Can't read sslkey '{}': {}", key, e)))?;
+        Ok(Some((certs, key)))
+    }
+
+    /// Load the root certificates to verify the server against
+    fn root_store(&self) -> PyResult<RootCertStore> {
+        let mut roots = RootCertStore::empty();
+        match self.root_cert.as_deref() {
+            None | Some("system") => {
+                let native = rustls_native_certs::load_native_certs();
+                roots.add_parsable_certificates(native.certs);
+                if roots.is_empty() {
+                    return Err(tls_config_error(
+                        "No trusted root certificates found on this system; set sslrootcert to a PEM file",
+                    ));
+                }
+            }
+            Some(path) => {
+                roots.add_parsable_certificates(read_certificates(path, "sslrootcert")?);
+                if roots.is_empty() {
+                    return Err(tls_config_error(&format!("sslrootcert '{}' contains no usable certificates", path)));
+                }
+            }
+        }
+        Ok(roots)
+    }
+}
+
+/// Read every certificate in a PEM file; `option` names it in errors
+fn read_certificates(path: &str, option: &str) -> PyResult<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| tls_config_error(&format!("Can't read {} '{}': {}", option, path, e)))?;
+    if certs.is_empty() {
+        return Err(tls_config_error(&format!("{} '{}' contains no certificates", option, path)));
+    }
+    Ok(certs)
+}
+
+/// Accepts any server certificate, for the modes that encrypt without
+/// verifying; handshake signatures are still checked
+#[derive(Debug)]
+struct NoCertificateVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Remove the given options from a connection string, returning the rest
+/// of the string and the removed options with their decoded values
+fn extract_options(connection_string: &str, keys: &[&str]) -> (String, Vec<(String, String)>) {
+    let mut taken = Vec::new();
+    if connection_string.starts_with("postgresql://") || connection_string.starts_with("postgres://") {
+        let Some((base, query)) = connection_string.split_once('?') else {
+            return (connection_string.to_string(), taken);
+        };
+        let mut kept = Vec::new();
+        for param in query.split('&') {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            if keys.contains(&key) {
+                taken.push((key.to_string(), percent_decode(value)));
+            } else {
+                kept.push(param);
+            }
+        }
+        let rest = if kept.is_empty() { base.to_string() } else { format!("{}?{}", base, kept.join("&")) };
+        return (rest, taken);
+    }
+
+    // key=value pairs separated by whitespace; values may be single-quoted
+    let mut rest = String::new();
+    let mut chars = connection_string.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut key = String::new();
+        while let Some(&(_, c)) = chars.peek() {
+            if c == '=' || c.is_whitespace() {
+                break;
+            }
+            key.push(c);
+            chars.next();
+        }
+        while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        chars.next_if(|&(_, c)| c == '=');
+        while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if(|&(_, c)| c == '\'').is_some() {
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                    '\'' => break,
+                    _ => value.push(c),
+                }
+            }
+        } else {
+            while let Some((_, c)) = chars.next_if(|&(_, c)| !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+        let end = chars.peek().map_or(connection_string.len(), |&(i, _)| i);
+        if keys.contains(&key.as_str()) {
+            taken.push((key, value));
+        } else {
+            if !rest.is_empty() {
+                rest.push(' ');
+            }
+            rest.push_str(connection_string[start..end].trim_end());
+        }
+    }
+    (rest, taken)
+}
+
+/// Decode %XX escapes in a URL query value
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_options() {
+        let (rest, taken) = extract_options(
+            "postgresql://u@db.example.com/app?sslmode=verify-full&application_name=x&sslrootcert=%2Fetc%2Frds.pem",
+            &TLS_OPTIONS,
+        );
+        assert_eq!(rest, "postgresql://u@db.example.com/app?application_name=x");
+        assert_eq!(taken, vec![
+            ("sslmode".to_string(), "verify-full".to_string()),
+            ("sslrootcert".to_string(), "/etc/rds.pem".to_string()),
+        ]);
+
+        let (rest, taken) = extract_options("postgresql://u@localhost/app?sslmode=require", &TLS_OPTIONS);
+        assert_eq!(rest, "postgresql://u@localhost/app");
+        assert_eq!(taken, vec![("sslmode".to_string(), "require".to_string())]);
+
+        let (rest, taken) = extract_options(
+            "host=localhost sslmode = disable user=postgres sslrootcert='/my certs/ca.pem' dbname=app sslcert=c.pem sslkey=k.pem",
+            &TLS_OPTIONS,
+        );
+        assert_eq!(rest, "host=localhost user=postgres dbname=app");
+        assert_eq!(taken, vec![
+            ("sslmode".to_string(), "disable".to_string()),
+            ("sslrootcert".to_string(), "/my certs/ca.pem".to_string()),
+            ("sslcert".to_string(), "c.pem".to_string()),
+            ("sslkey".to_string(), "k.pem".to_string()),
+        ]);
+    }
+}
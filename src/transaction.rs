@@ -1,21 +1,48 @@
 use pyo3::prelude::*;
 use pyo3::types::PyList;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio_postgres::Client;
-
-use crate::error::{map_db_error, transaction_completed_error};
-use crate::row::Row;
+use deadpool_postgres::Object;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tokio_postgres::{Client, Config};
+
+use crate::error::{connection_released_error, map_db_error, transaction_completed_error};
+use crate::paramstyle::ParamStyle;
+use crate::quote::{object_ident, quote_literal};
+use crate::row::{query_single, Row};
 use crate::runtime::RuntimeManager;
-use crate::types::py_objects_to_postgres_values;
+use crate::types::{py_objects_to_postgres_values, ConversionOptions};
+
+/// The client a transaction runs on: a connection's own client, or one
+/// checked out of a pool with `acquire()` (None once it has been released)
+#[derive(Clone)]
+pub enum SharedClient {
+    Connection(Arc<Mutex<Client>>),
+    Pooled(Arc<Mutex<Option<Object>>>),
+}
+
+impl SharedClient {
+    /// Lock the client for the duration of a statement
+    pub async fn lock(&self) -> PyResult<MappedMutexGuard<'_, Client>> {
+        match self {
+            Self::Connection(client) => Ok(MutexGuard::map(client.lock().await, |client| client)),
+            Self::Pooled(object) => MutexGuard::try_map(object.lock().await, |object| {
+                object.as_mut().map(|object| &mut ***object)
+            })
+            .map_err(|_| connection_released_error()),
+        }
+    }
+}
 
 /// Represents a database transaction using manual SQL commands
 /// This avoids lifetime issues with tokio_postgres::Transaction
 #[pyclass]
 pub struct Transaction {
-    client: Arc<Mutex<Client>>,
+    client: SharedClient,
     runtime: RuntimeManager,
     is_completed: Arc<Mutex<bool>>,
+    conversion: ConversionOptions,
+    // Placeholder style of the connection the transaction was started on
+    paramstyle: ParamStyle,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -47,56 +74,79 @@ impl IsolationLevel {
     }
 }
 
+/// Make `level` the default isolation of every session opened with `config`
+///
+/// The setting is passed as a startup option, so it is the session default
+/// itself and survives `RESET ALL` / `DISCARD ALL` on pooled connections.
+pub fn apply_default_isolation(config: &mut Config, level: &str) -> PyResult<()> {
+    let isolation_level = IsolationLevel::from_str(level)
+        .ok_or_else(|| crate::error::type_conversion_error("valid isolation level", level))?;
+
+    // Spaces inside an option value must be backslash-escaped
+    let option = format!(
+        "-c default_transaction_isolation={}",
+        isolation_level.to_sql().to_lowercase().replace(' ', "\\ ")
+    );
+    let options = match config.get_options() {
+        Some(existing) if !existing.is_empty() => format!("{} {}", existing, option),
+        _ => option,
+    };
+    config.options(&options);
+    Ok(())
+}
+
 #[pymethods]
 impl Transaction {
     /// Execute a query within the transaction that doesn't return rows
-    pub fn execute(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<u64> {
+    pub fn execute(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<u64> {
         self.check_active()?;
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
 
         let postgres_params = if let Some(p) = params {
             let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
-            py_objects_to_postgres_values(py, &params_vec)?
+            py_objects_to_postgres_values(py, &params_vec, self.conversion)?
         } else {
             Vec::new()
         };
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
                 .iter()
                 .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
                 .collect();
 
-            client.execute(query, &params_refs[..]).await.map_err(map_db_error)
+            client.execute(query.as_ref(), &params_refs[..]).await.map_err(map_db_error)
         })
     }
 
     /// Execute a query within the transaction and return all rows
-    pub fn query(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<PyObject> {
+    pub fn query(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<PyObject> {
         self.check_active()?;
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
 
         let postgres_params = if let Some(p) = params {
             let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
-            py_objects_to_postgres_values(py, &params_vec)?
+            py_objects_to_postgres_values(py, &params_vec, self.conversion)?
         } else {
             Vec::new()
         };
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         let rows = self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
                 .iter()
                 .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
                 .collect();
 
-            client.query(query, &params_refs[..]).await.map_err(map_db_error)
+            client.query(query.as_ref(), &params_refs[..]).await.map_err(map_db_error)
         })?;
 
         let py_rows = PyList::empty(py);
         for row in rows {
-            let py_row = Row::from_tokio_row(py, &row)?;
+            let py_row = Row::from_tokio_row(py, &row, self.conversion)?;
             let py_cell = Py::new(py, py_row)?;
             py_rows.append(py_cell)?;
         }
@@ -105,64 +155,77 @@ impl Transaction {
     }
 
     /// Execute a query within the transaction and return exactly one row
-    pub fn query_one(&self, py: Python, query: &str, params: Option<&PyList>) -> PyResult<Py<Row>> {
+    ///
+    /// Raises NoDataError for no rows and TooManyRowsError for more than one.
+    pub fn query_one(&self, py: Python, query: &str, params: Option<&PyAny>) -> PyResult<Py<Row>> {
         self.check_active()?;
+        let (query, params) = self.paramstyle.bind(py, query, params)?;
 
         let postgres_params = if let Some(p) = params {
             let params_vec: Vec<PyObject> = p.iter().map(|item| item.into()).collect();
-            py_objects_to_postgres_values(py, &params_vec)?
+            py_objects_to_postgres_values(py, &params_vec, self.conversion)?
         } else {
             Vec::new()
         };
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         let row = self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             let params_refs: Vec<&(dyn postgres_types::ToSql + Sync)> = postgres_params
                 .iter()
                 .map(|p| p.as_ref() as &(dyn postgres_types::ToSql + Sync))
                 .collect();
 
-            client.query_one(query, &params_refs[..])
-                .await
-                .map_err(map_db_error)
+            query_single(&client, query.as_ref(), &params_refs[..]).await
         })?;
 
-        let row_obj = Row::from_tokio_row(py, &row)?;
+        let row_obj = Row::from_tokio_row(py, &row, self.conversion)?;
         Ok(Py::new(py, row_obj)?)
     }
 
     /// Commit the transaction
-    pub fn commit(&self) -> PyResult<()> {
+    ///
+    /// With `chain=True` (COMMIT AND CHAIN, PostgreSQL 12+) a new
+    /// transaction with the same isolation level and access mode starts
+    /// right away, and this object stays active for it.
+    #[pyo3(signature = (chain=false))]
+    pub fn commit(&self, chain: bool) -> PyResult<()> {
         self.check_active()?;
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         let is_completed = Arc::clone(&self.is_completed);
+        let sql = if chain { "COMMIT AND CHAIN" } else { "COMMIT" };
 
         self.runtime.block_on(async move {
-            let client = client.lock().await;
-            client.batch_execute("COMMIT").await.map_err(map_db_error)?;
+            let client = client.lock().await?;
+            client.batch_execute(sql).await.map_err(map_db_error)?;
 
             let mut completed = is_completed.lock().await;
-            *completed = true;
+            *completed = !chain;
 
             Ok(())
         })
     }
 
     /// Roll back the transaction
-    pub fn rollback(&self) -> PyResult<()> {
+    ///
+    /// With `chain=True` (ROLLBACK AND CHAIN, PostgreSQL 12+) a new
+    /// transaction with the same isolation level and access mode starts
+    /// right away, and this object stays active for it.
+    #[pyo3(signature = (chain=false))]
+    pub fn rollback(&self, chain: bool) -> PyResult<()> {
         self.check_active()?;
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         let is_completed = Arc::clone(&self.is_completed);
+        let sql = if chain { "ROLLBACK AND CHAIN" } else { "ROLLBACK" };
 
         self.runtime.block_on(async move {
-            let client = client.lock().await;
-            client.batch_execute("ROLLBACK").await.map_err(map_db_error)?;
+            let client = client.lock().await?;
+            client.batch_execute(sql).await.map_err(map_db_error)?;
 
             let mut completed = is_completed.lock().await;
-            *completed = true;
+            *completed = !chain;
 
             Ok(())
         })
@@ -172,18 +235,11 @@ impl Transaction {
     pub fn savepoint(&self, name: &str) -> PyResult<()> {
         self.check_active()?;
 
-        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.is_empty() {
-            return Err(crate::error::type_conversion_error(
-                "valid SQL identifier",
-                name,
-            ));
-        }
-
-        let client = Arc::clone(&self.client);
-        let sql = format!("SAVEPOINT {}", name);
+        let client = self.client.clone();
+        let sql = format!("SAVEPOINT {}", object_ident(name)?);
         
         self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             client.batch_execute(&sql).await.map_err(map_db_error)?;
             Ok(())
         })
@@ -193,18 +249,11 @@ impl Transaction {
     pub fn rollback_to(&self, name: &str) -> PyResult<()> {
         self.check_active()?;
 
-        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.is_empty() {
-            return Err(crate::error::type_conversion_error(
-                "valid SQL identifier",
-                name,
-            ));
-        }
-
-        let client = Arc::clone(&self.client);
-        let sql = format!("ROLLBACK TO SAVEPOINT {}", name);
+        let client = self.client.clone();
+        let sql = format!("ROLLBACK TO SAVEPOINT {}", object_ident(name)?);
 
         self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             client.batch_execute(&sql).await.map_err(map_db_error)?;
             Ok(())
         })
@@ -214,18 +263,11 @@ impl Transaction {
     pub fn release_savepoint(&self, name: &str) -> PyResult<()> {
         self.check_active()?;
 
-        if !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.is_empty() {
-            return Err(crate::error::type_conversion_error(
-                "valid SQL identifier",
-                name,
-            ));
-        }
-
-        let client = Arc::clone(&self.client);
-        let sql = format!("RELEASE SAVEPOINT {}", name);
+        let client = self.client.clone();
+        let sql = format!("RELEASE SAVEPOINT {}", object_ident(name)?);
 
         self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             client.batch_execute(&sql).await.map_err(map_db_error)?;
             Ok(())
         })
@@ -238,14 +280,14 @@ impl Transaction {
         let isolation_level = IsolationLevel::from_str(level)
             .ok_or_else(|| crate::error::type_conversion_error("valid isolation level", level))?;
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         let sql = format!(
             "SET TRANSACTION ISOLATION LEVEL {}",
             isolation_level.to_sql()
         );
 
         self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             client.batch_execute(&sql).await.map_err(map_db_error)?;
             Ok(())
         })
@@ -255,7 +297,7 @@ impl Transaction {
     pub fn set_read_only(&self, read_only: bool) -> PyResult<()> {
         self.check_active()?;
 
-        let client = Arc::clone(&self.client);
+        let client = self.client.clone();
         let sql = if read_only {
             "SET TRANSACTION READ ONLY"
         } else {
@@ -263,12 +305,47 @@ impl Transaction {
         };
 
         self.runtime.block_on(async move {
-            let client = client.lock().await;
+            let client = client.lock().await?;
             client.batch_execute(sql).await.map_err(map_db_error)?;
             Ok(())
         })
     }
 
+    /// Set a configuration parameter for the rest of this transaction
+    ///
+    /// Issues `SET LOCAL`, so the setting reverts on commit or rollback. This
+    /// is the safe way to scope per-tenant state (e.g. `role` or
+    /// `app.current_tenant` for row-level security) on pooled connections.
+    ///
+    /// Args:
+    ///     param: Parameter name, e.g. "role" or "app.current_tenant"
+    ///     value: Parameter value, sent as a quoted literal
+    pub fn set_local(&self, param: &str, value: &str) -> PyResult<()> {
+        self.check_active()?;
+
+        let valid_param = !param.is_empty()
+            && param
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'));
+        if !valid_param {
+            return Err(crate::error::type_conversion_error(
+                "valid SQL identifier",
+                param,
+            ));
+        }
+
+        let client = self.client.clone();
+        let value = quote_literal(value)
+            .ok_or_else(|| crate::error::type_conversion_error("text without NUL characters", value))?;
+        let sql = format!("SET LOCAL {} = {}", param, value);
+
+        self.runtime.block_on(async move {
+            let client = client.lock().await?;
+            client.batch_execute(&sql).await.map_err(map_db_error)?;
+            Ok(())
+        })
+    }
+
     /// Check if the transaction is still active
     pub fn is_active(&self) -> PyResult<bool> {
         let is_completed = self.is_completed.try_lock().map_err(|_| {
@@ -280,13 +357,18 @@ impl Transaction {
     /// Context manager entry
     fn __enter__(&self, _py: Python) -> PyResult<Self> {
         Ok(Self {
-            client: Arc::clone(&self.client),
+            client: self.client.clone(),
             runtime: self.runtime.clone(),
             is_completed: Arc::clone(&self.is_completed),
+            conversion: self.conversion,
+            paramstyle: self.paramstyle,
         })
     }
 
-    /// Context manager exit
+    /// Context manager exit - commits on success, rolls back on an exception
+    ///
+    /// A transaction already committed or rolled back inside the block is
+    /// left as it is.
     fn __exit__(
         &self,
         _py: Python,
@@ -294,13 +376,14 @@ impl Transaction {
         _exc_val: Option<PyObject>,
         _exc_tb: Option<PyObject>,
     ) -> PyResult<bool> {
+        if !self.is_active()? {
+            return Ok(false);
+        }
         if exc_type.is_some() {
-            if let Ok(guard) = self.is_completed.try_lock() {
-                if !*guard {
-                    drop(guard);
-                    let _ = self.rollback();
-                }
-            }
+            // Keep the original exception rather than a rollback failure
+            let _ = self.rollback(false);
+        } else {
+            self.commit(false)?;
         }
         Ok(false)
     }
@@ -308,22 +391,34 @@ impl Transaction {
 
 impl Transaction {
     /// Create a new transaction using manual BEGIN command
-    pub fn new(client: Arc<Mutex<Client>>, runtime: RuntimeManager) -> PyResult<Self> {
+    pub fn new(
+        client: SharedClient,
+        runtime: RuntimeManager,
+        conversion: ConversionOptions,
+        paramstyle: ParamStyle,
+    ) -> PyResult<Self> {
         let txn = Self {
             client,
             runtime: runtime.clone(),
             is_completed: Arc::new(Mutex::new(false)),
+            conversion,
+            paramstyle,
         };
         
         // Execute BEGIN to start transaction
         runtime.block_on(async {
-            let client = txn.client.lock().await;
+            let client = txn.client.lock().await?;
             client.batch_execute("BEGIN").await.map_err(map_db_error)
         })?;
         
         Ok(txn)
     }
 
+    /// Flag set once the transaction is committed or rolled back
+    pub fn completion(&self) -> Arc<Mutex<bool>> {
+        Arc::clone(&self.is_completed)
+    }
+
     /// Check if transaction is still active
     fn check_active(&self) -> PyResult<()> {
         if *self.is_completed.try_lock().map_err(|_| {
@@ -334,4 +429,4 @@ impl Transaction {
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,17 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 use compact_str::CompactString;
 use lru::LruCache;
 use once_cell::sync::Lazy;
-use postgres_types::ToSql;
-use pyo3::types::{PyBool, PyFloat, PyInt, PyString};
-use pyo3::{IntoPy, PyObject, PyResult, Python};
+use postgres_types::{FromSql, ToSql};
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{IntoPyDict, PyBool, PyByteArray, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyType};
+use pyo3::{IntoPy, Py, PyAny, PyErr, PyObject, PyResult, Python};
 use smallvec::SmallVec;
 use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::{not_supported_error, ConversionError, DataError};
+use crate::json::{json_to_py, py_to_json, write_json, write_json_path, JsonText};
 
 // String cache for common database values
 static STRING_CACHE: Lazy<Mutex<LruCache<String, CompactString>>> =
@@ -23,6 +29,304 @@ fn intern_string(s: String) -> CompactString {
     }
 }
 
+/// Per-connection settings for converting values between Python and PostgreSQL
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConversionOptions {
+    /// Raise `DataError` instead of silently losing precision when binding,
+    /// or returning None when a column value can't be decoded
+    pub strict: bool,
+    /// Decode INTERVAL as an ISO 8601 duration string (e.g. "P1Y2M3DT4H")
+    /// instead of a `datetime.timedelta`
+    pub interval_iso: bool,
+    /// Decode zero-scale NUMERIC values as `int` instead of `decimal.Decimal`
+    pub numeric_int: bool,
+    /// Types returned in their PostgreSQL text representation
+    pub text_types: TextTypes,
+    /// Zone TIMESTAMPTZ values are converted to instead of UTC
+    pub timezone: Option<DisplayZone>,
+}
+
+/// A `zoneinfo.ZoneInfo` that TIMESTAMPTZ values are returned in
+///
+/// Zones are created once per name and kept for the life of the process,
+/// so the handle is `Copy` like the rest of `ConversionOptions`.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayZone(&'static PyObject);
+
+static DISPLAY_ZONES: Lazy<Mutex<std::collections::HashMap<String, &'static PyObject>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+impl DisplayZone {
+    /// Look up an IANA zone name such as "Europe/Berlin"
+    pub fn from_name(py: Python, name: &str) -> PyResult<Self> {
+        if let Some(zone) = DISPLAY_ZONES.lock().unwrap().get(name) {
+            return Ok(DisplayZone(zone));
+        }
+        // Created without holding the lock, since the import can release the GIL
+        let zone: PyObject = py
+            .import("zoneinfo")
+            .and_then(|zoneinfo| zoneinfo.getattr("ZoneInfo")?.call1((name,)))
+            .map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!("Unknown time zone '{}': {}", name, e))
+            })?
+            .into();
+        let mut zones = DISPLAY_ZONES.lock().unwrap();
+        let zone = *zones.entry(name.to_string()).or_insert_with(|| Box::leak(Box::new(zone)));
+        Ok(DisplayZone(zone))
+    }
+}
+
+/// Column types whose values are returned as their PostgreSQL text
+/// representation rather than decoded into Python objects
+///
+/// Results always arrive in binary format; for these types the binary value
+/// maps straight onto the text form (JSONB is its JSON text behind a version
+/// byte, bytea is rendered as "\x" plus hex), which skips building Python
+/// objects for large values. Arrays of a type follow the element type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextTypes {
+    pub json: bool,
+    pub jsonb: bool,
+    pub bytea: bool,
+}
+
+impl TextTypes {
+    /// Parse type names ("json", "jsonb", "bytea"), case-insensitively
+    pub fn from_names(names: &[String]) -> PyResult<Self> {
+        let mut types = TextTypes::default();
+        for name in names {
+            match name.to_lowercase().as_str() {
+                "json" => types.json = true,
+                "jsonb" => types.jsonb = true,
+                "bytea" => types.bytea = true,
+                _ => return Err(crate::error::type_conversion_error("'json', 'jsonb' or 'bytea'", name)),
+            }
+        }
+        Ok(types)
+    }
+
+    /// Whether values of `ty` (or of its elements, for arrays) are returned as text
+    pub fn includes(&self, ty: &postgres_types::Type) -> bool {
+        match *ty {
+            postgres_types::Type::JSON | postgres_types::Type::JSON_ARRAY => self.json,
+            postgres_types::Type::JSONB | postgres_types::Type::JSONB_ARRAY => self.jsonb,
+            postgres_types::Type::BYTEA | postgres_types::Type::BYTEA_ARRAY => self.bytea,
+            _ => false,
+        }
+    }
+}
+
+// Python standard library classes, looked up once per interpreter
+static DATETIME_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static UUID_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static DECIMAL_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static TIMEDELTA_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static DATE_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static TIME_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static ARRAY_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static UTC: GILOnceCell<PyObject> = GILOnceCell::new();
+
+/// Fetch a standard library class through a per-interpreter cache
+fn cached_type<'py>(
+    py: Python<'py>,
+    cell: &'static GILOnceCell<Py<PyType>>,
+    module: &str,
+    name: &str,
+) -> PyResult<&'py PyType> {
+    let ty = cell.get_or_try_init(py, || {
+        let ty: &PyType = py.import(module)?.getattr(name)?.downcast()?;
+        Ok::<_, PyErr>(Py::from(ty))
+    })?;
+    Ok(ty.as_ref(py))
+}
+
+/// Build a Python `uuid.UUID` from its 16 raw bytes
+fn uuid_to_py(py: Python, uuid: &Uuid) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("bytes", PyBytes::new(py, uuid.as_bytes()))?;
+    Ok(cached_type(py, &UUID_TYPE, "uuid", "UUID")?.call((), Some(kwargs))?.into())
+}
+
+/// Build a Python value from NUMERIC text: a `decimal.Decimal`, or with
+/// `numeric_int` an int for zero-scale values (whole numbers of any size)
+fn numeric_to_py(py: Python, text: &str, options: ConversionOptions) -> PyResult<PyObject> {
+    if options.numeric_int && is_integral(text) {
+        return match text.parse::<i64>() {
+            Ok(val) => Ok(val.into_py(py)),
+            Err(_) => Ok(py.get_type::<PyInt>().call1((text,))?.into()),
+        };
+    }
+    Ok(cached_type(py, &DECIMAL_TYPE, "decimal", "Decimal")?.call1((text,))?.into())
+}
+
+/// Convert a `uuid.UUID` instance into a `Uuid`, or None for any other object
+fn extract_uuid(py: Python, obj: &PyAny) -> PyResult<Option<Uuid>> {
+    if !obj.is_instance(cached_type(py, &UUID_TYPE, "uuid", "UUID")?)? {
+        return Ok(None);
+    }
+    let bytes: &[u8] = obj.getattr("bytes")?.extract()?;
+    Uuid::from_slice(bytes)
+        .map(Some)
+        .map_err(|e| DataError::new_err(e.to_string()))
+}
+
+/// Build a `datetime.datetime`, with `tzinfo=timezone.utc` when `utc` is set
+///
+/// With a display zone, UTC values are converted to it; the instant is the
+/// same, only the wall-clock time and tzinfo differ. `datetime.min/max`
+/// stand for the infinities and stay in UTC.
+fn datetime_to_py(py: Python, dt: NaiveDateTime, utc: bool, zone: Option<DisplayZone>) -> PyResult<PyObject> {
+    let tzinfo = if utc {
+        UTC.get_or_try_init(py, || {
+            Ok::<_, PyErr>(py.import("datetime")?.getattr("timezone")?.getattr("utc")?.into())
+        })?
+        .as_ref(py)
+    } else {
+        py.None().into_ref(py)
+    };
+    let datetime = cached_type(py, &DATETIME_TYPE, "datetime", "datetime")?;
+    let value = datetime.call1((
+        dt.year(),
+        dt.month(),
+        dt.day(),
+        dt.hour(),
+        dt.minute(),
+        dt.second(),
+        dt.nanosecond() / 1_000,
+        tzinfo,
+    ))?;
+    match zone {
+        Some(zone) if utc && dt != py_datetime_min() && dt != py_datetime_max() => {
+            Ok(value.call_method1(pyo3::intern!(py, "astimezone"), (zone.0,))?.into())
+        }
+        _ => Ok(value.into()),
+    }
+}
+
+/// Build a `datetime.date`
+fn date_to_py(py: Python, date: NaiveDate) -> PyResult<PyObject> {
+    let ty = cached_type(py, &DATE_TYPE, "datetime", "date")?;
+    Ok(ty.call1((date.year(), date.month(), date.day()))?.into())
+}
+
+/// Build a `datetime.time`
+fn time_to_py(py: Python, time: NaiveTime) -> PyResult<PyObject> {
+    let ty = cached_type(py, &TIME_TYPE, "datetime", "time")?;
+    Ok(ty
+        .call1((time.hour(), time.minute(), time.second(), time.nanosecond() / 1_000))?
+        .into())
+}
+
+/// Convert a `datetime.date` (but not a `datetime.datetime`) into a `NaiveDate`,
+/// or None for any other object
+fn extract_date(py: Python, obj: &PyAny) -> PyResult<Option<NaiveDate>> {
+    if !obj.is_instance(cached_type(py, &DATE_TYPE, "datetime", "date")?)? {
+        return Ok(None);
+    }
+    NaiveDate::from_ymd_opt(
+        obj.getattr("year")?.extract()?,
+        obj.getattr("month")?.extract()?,
+        obj.getattr("day")?.extract()?,
+    )
+    .map(Some)
+    .ok_or_else(|| crate::error::type_conversion_error("valid date", &obj.to_string()))
+}
+
+/// Convert a naive `datetime.time` into a `NaiveTime`, or None for any other object
+///
+/// TIME has no time zone, so aware times are rejected rather than having
+/// their offset silently dropped.
+fn extract_time(py: Python, obj: &PyAny) -> PyResult<Option<NaiveTime>> {
+    if !obj.is_instance(cached_type(py, &TIME_TYPE, "datetime", "time")?)? {
+        return Ok(None);
+    }
+    if !obj.getattr("tzinfo")?.is_none() {
+        return Err(crate::error::type_conversion_error("naive datetime.time", &obj.to_string()));
+    }
+    NaiveTime::from_hms_micro_opt(
+        obj.getattr("hour")?.extract()?,
+        obj.getattr("minute")?.extract()?,
+        obj.getattr("second")?.extract()?,
+        obj.getattr("microsecond")?.extract()?,
+    )
+    .map(Some)
+    .ok_or_else(|| crate::error::type_conversion_error("valid time", &obj.to_string()))
+}
+
+/// Copy the contents of a `bytes` or `bytearray`, or None for any other object
+fn extract_bytes(obj: &PyAny) -> Option<Vec<u8>> {
+    if let Ok(bytes) = obj.downcast::<PyBytes>() {
+        Some(bytes.as_bytes().to_vec())
+    } else if let Ok(bytes) = obj.downcast::<PyByteArray>() {
+        Some(bytes.to_vec())
+    } else {
+        None
+    }
+}
+
+/// Plain decimal text of a `decimal.Decimal`, or None for any other object
+///
+/// Formatted with `'f'` so exponents are expanded (`Decimal('1.5E+3')` gives
+/// "1500") while the scale is kept; every NaN binds as NUMERIC's NaN.
+fn extract_decimal(py: Python, obj: &PyAny) -> PyResult<Option<String>> {
+    if !obj.is_instance(cached_type(py, &DECIMAL_TYPE, "decimal", "Decimal")?)? {
+        return Ok(None);
+    }
+    if obj.call_method0("is_nan")?.is_true()? {
+        return Ok(Some("NaN".to_string()));
+    }
+    Ok(Some(obj.call_method1("__format__", ("f",))?.extract()?))
+}
+
+/// A Python `datetime.datetime` split into its wall-clock value and UTC offset
+enum PyDateTimeValue {
+    Naive(NaiveDateTime),
+    Aware(DateTime<Utc>),
+}
+
+/// Convert a `datetime.datetime` into a chrono value
+///
+/// Aware datetimes are normalized to UTC through `utcoffset()` rather than by
+/// inspecting the tzinfo, so `datetime.timezone`, `zoneinfo.ZoneInfo` and pytz
+/// zones all resolve to the same instant.
+fn extract_datetime(obj: &PyAny) -> PyResult<PyDateTimeValue> {
+    let naive = NaiveDate::from_ymd_opt(
+        obj.getattr("year")?.extract()?,
+        obj.getattr("month")?.extract()?,
+        obj.getattr("day")?.extract()?,
+    )
+    .and_then(|date| {
+        date.and_hms_micro_opt(
+            obj.getattr("hour").ok()?.extract().ok()?,
+            obj.getattr("minute").ok()?.extract().ok()?,
+            obj.getattr("second").ok()?.extract().ok()?,
+            obj.getattr("microsecond").ok()?.extract().ok()?,
+        )
+    })
+    .ok_or_else(|| crate::error::type_conversion_error("valid datetime", &obj.to_string()))?;
+
+    let offset = obj.call_method0("utcoffset")?;
+    if offset.is_none() {
+        return Ok(PyDateTimeValue::Naive(naive));
+    }
+
+    let offset_micros = timedelta_micros(
+        offset.getattr("days")?.extract()?,
+        offset.getattr("seconds")?.extract()?,
+        offset.getattr("microseconds")?.extract()?,
+    );
+    let utc = naive - chrono::Duration::microseconds(offset_micros);
+    Ok(PyDateTimeValue::Aware(DateTime::from_naive_utc_and_offset(utc, Utc)))
+}
+
+/// Total microseconds of a `datetime.timedelta` given its normalized fields
+///
+/// Python normalizes negative durations so only `days` is negative, e.g.
+/// UTC-05:00 is stored as `days=-1, seconds=68400`.
+fn timedelta_micros(days: i64, seconds: i64, microseconds: i64) -> i64 {
+    (days * 86_400 + seconds) * 1_000_000 + microseconds
+}
+
 /// High-performance PostgreSQL value type with proper binary protocol support
 #[derive(Debug, Clone)]
 pub enum PostgresValue {
@@ -34,6 +338,13 @@ pub enum PostgresValue {
     Float32(f32),
     Float64(f64),
     String(String),
+    Uuid([u8; 16]),
+    /// Serialized JSON text, bound as JSON or JSONB
+    Json(String),
+    /// Plain decimal text of a `decimal.Decimal` or an int beyond 64 bits,
+    /// bound exactly as NUMERIC (or converted for other types)
+    Numeric(String),
+    Bytes(Vec<u8>),
 }
 
 impl ToSql for PostgresValue {
@@ -42,38 +353,555 @@ impl ToSql for PostgresValue {
         ty: &postgres_types::Type,
         out: &mut bytes::BytesMut,
     ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        // Type checks happen per variant, since NULL binds to any type
         match self {
             PostgresValue::Null => Ok(postgres_types::IsNull::Yes),
-            PostgresValue::Bool(v) => v.to_sql(ty, out),
-            PostgresValue::Int16(v) => v.to_sql(ty, out),
-            PostgresValue::Int32(v) => v.to_sql(ty, out),
-            PostgresValue::Int64(v) => v.to_sql(ty, out),
-            PostgresValue::Float32(v) => v.to_sql(ty, out),
-            PostgresValue::Float64(v) => v.to_sql(ty, out),
-            PostgresValue::String(v) => v.to_sql(ty, out),
+            PostgresValue::Bool(v) => v.to_sql_checked(ty, out),
+            PostgresValue::Int16(v) => write_int(*v as i64, ty, out),
+            PostgresValue::Int32(v) => write_int(*v as i64, ty, out),
+            PostgresValue::Int64(v) => write_int(*v, ty, out),
+            PostgresValue::Float32(v) => v.to_sql_checked(ty, out),
+            PostgresValue::Float64(v) => v.to_sql_checked(ty, out),
+            PostgresValue::String(v) => write_text(v, ty, out),
+            PostgresValue::Uuid(v) => Uuid::from_bytes(*v).to_sql_checked(ty, out),
+            PostgresValue::Json(v) => write_json(v, ty, out),
+            PostgresValue::Numeric(v) => write_decimal(v, ty, out),
+            PostgresValue::Bytes(v) => write_bytes(v, ty, out),
+        }
+    }
+
+    fn accepts(_ty: &postgres_types::Type) -> bool {
+        true
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// A Python list parameter, bound as an array or as JSON
+///
+/// Which one is only known once the server reports the parameter type, so
+/// both encodings are prepared up front: a JSON/JSONB parameter gets the
+/// JSON text, any other type the array.
+#[derive(Debug)]
+pub struct ListParam {
+    array: Option<Box<dyn ToSql + Sync + Send>>,
+    json: Option<String>,
+}
+
+impl ToSql for ListParam {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        match (&self.json, &self.array) {
+            (Some(json), _) if JsonText::accepts(ty) => write_json(json, ty, out),
+            (_, Some(array)) => array.to_sql_checked(ty, out),
+            _ if matches!(ty.kind(), postgres_types::Kind::Array(_)) => Err(format!(
+                "cannot bind a list with nested lists or mixed element types to type {}; \
+                 only one-dimensional arrays of one element type are supported",
+                ty
+            )
+            .into()),
+            _ => Err(format!("cannot bind a list to type {}", ty).into()),
         }
     }
 
+    fn accepts(_ty: &postgres_types::Type) -> bool {
+        true
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// PostgreSQL tuple identifier (the `ctid` system column)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tid {
+    pub block: u32,
+    pub offset: u16,
+}
+
+impl<'a> FromSql<'a> for Tid {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Binary format: 4-byte block number followed by 2-byte line pointer
+        if raw.len() != 6 {
+            return Err(format!("invalid tid length: {}", raw.len()).into());
+        }
+        Ok(Tid {
+            block: u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]),
+            offset: u16::from_be_bytes([raw[4], raw[5]]),
+        })
+    }
+
     fn accepts(ty: &postgres_types::Type) -> bool {
-        matches!(
-            *ty,
-            postgres_types::Type::BOOL
-                | postgres_types::Type::INT2
-                | postgres_types::Type::INT4
-                | postgres_types::Type::INT8
-                | postgres_types::Type::FLOAT4
-                | postgres_types::Type::FLOAT8
-                | postgres_types::Type::NUMERIC
-                | postgres_types::Type::TEXT
-                | postgres_types::Type::VARCHAR
-                | postgres_types::Type::CHAR
-                | postgres_types::Type::BPCHAR
-        )
+        *ty == postgres_types::Type::TID
+    }
+}
+
+/// Exact decimal text of a NUMERIC value, e.g. "-12.340" or "NaN"
+///
+/// Decoded straight from the binary base-10000 digits so no value ever
+/// passes through a float; Python side wraps it in `decimal.Decimal`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumericText(pub String);
+
+impl<'a> FromSql<'a> for NumericText {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Header: ndigits, weight, sign, dscale (all 16-bit), then base-10000 digits
+        if raw.len() < 8 {
+            return Err("invalid numeric header".into());
+        }
+        let word = |i: usize| u16::from_be_bytes([raw[2 * i], raw[2 * i + 1]]);
+        let ndigits = word(0) as usize;
+        let weight = word(1) as i16 as i32;
+        let sign = word(2);
+        let dscale = word(3) as usize;
+        if raw.len() != 8 + 2 * ndigits {
+            return Err("invalid numeric length".into());
+        }
+
+        match sign {
+            0xC000 => return Ok(NumericText("NaN".to_string())),
+            0xD000 => return Ok(NumericText("Infinity".to_string())),
+            0xF000 => return Ok(NumericText("-Infinity".to_string())),
+            0x0000 | 0x4000 => {}
+            _ => return Err(format!("invalid numeric sign: {:#x}", sign).into()),
+        }
+
+        let digit = |i: i32| {
+            if i >= 0 && (i as usize) < ndigits {
+                word(4 + i as usize)
+            } else {
+                0
+            }
+        };
+
+        let mut text = String::new();
+        if sign == 0x4000 {
+            text.push('-');
+        }
+        if weight < 0 {
+            text.push('0');
+        } else {
+            text.push_str(&digit(0).to_string());
+            for i in 1..=weight {
+                text.push_str(&format!("{:04}", digit(i)));
+            }
+        }
+        if dscale > 0 {
+            let mut fraction = String::with_capacity(dscale + 4);
+            let mut i = weight + 1;
+            while fraction.len() < dscale {
+                fraction.push_str(&format!("{:04}", digit(i)));
+                i += 1;
+            }
+            fraction.truncate(dscale);
+            text.push('.');
+            text.push_str(&fraction);
+        }
+
+        Ok(NumericText(text))
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        *ty == postgres_types::Type::NUMERIC
+    }
+}
+
+/// Write decimal text in the binary format of the parameter's type
+///
+/// NUMERIC keeps the exact value. Integer parameters take integral values
+/// that fit them, floating point parameters the nearest float; anything else
+/// binds as text.
+fn write_decimal(
+    text: &str,
+    ty: &postgres_types::Type,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    let name = match *ty {
+        postgres_types::Type::NUMERIC => return write_numeric(text, out),
+        postgres_types::Type::FLOAT4 | postgres_types::Type::FLOAT8 => match text.parse::<f64>() {
+            Ok(val) if *ty == postgres_types::Type::FLOAT4 => return (val as f32).to_sql(ty, out),
+            Ok(val) => return val.to_sql(ty, out),
+            Err(_) => return text.to_sql_checked(ty, out),
+        },
+        postgres_types::Type::INT2 => "smallint",
+        postgres_types::Type::INT4 => "integer",
+        postgres_types::Type::INT8 => "bigint",
+        postgres_types::Type::OID => "oid",
+        _ => return text.to_sql_checked(ty, out),
+    };
+    if !is_integral(text) {
+        return Err(ConversionError(format!("{} is not an integer, as type {} requires", text, name)).into());
+    }
+    match text.parse::<i64>() {
+        Ok(val) => write_int(val, ty, out),
+        Err(_) => Err(ConversionError(format!("{} is out of range for type {}", text, name)).into()),
+    }
+}
+
+/// Write a Python str as text, or as the JSON it holds when the parameter is
+/// JSON, JSONB or JSONPATH
+///
+/// Lets already-serialized JSON fill `data @> $1::jsonb` and path strings fill
+/// `data @@ $1`; the server validates the text and rejects malformed input.
+fn write_text(
+    text: &str,
+    ty: &postgres_types::Type,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    match *ty {
+        postgres_types::Type::JSON | postgres_types::Type::JSONB => write_json(text, ty, out),
+        postgres_types::Type::JSONPATH => write_json_path(text, out),
+        _ => text.to_sql_checked(ty, out),
+    }
+}
+
+/// Whether decimal text is a plain integer such as "-42" (no point, not NaN)
+fn is_integral(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Encode plain decimal text ("-12.340", "NaN", "Infinity") as binary NUMERIC
+///
+/// The inverse of `NumericText`: the digits after the point set the display
+/// scale, so trailing zeros survive the round trip.
+fn write_numeric(
+    text: &str,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    let header = |out: &mut bytes::BytesMut, ndigits: usize, weight: i32, sign: u16, dscale: usize| {
+        for word in [ndigits as u16, weight as i16 as u16, sign, dscale as u16] {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+    };
+
+    let special = match text {
+        "NaN" => Some(0xC000),
+        "Infinity" => Some(0xD000),
+        "-Infinity" => Some(0xF000),
+        _ => None,
+    };
+    if let Some(sign) = special {
+        header(out, 0, 0, sign, 0);
+        return Ok(postgres_types::IsNull::No);
+    }
+
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let valid = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (int_part.is_empty() && frac_part.is_empty()) || !valid(int_part) || !valid(frac_part) {
+        return Err(format!("invalid numeric value: {}", text).into());
+    }
+    let dscale = frac_part.len();
+    if dscale > 0x3FFF {
+        return Err(format!("numeric scale {} is out of range", dscale).into());
+    }
+
+    // Align both parts to base-10000 groups around the decimal point
+    let int_part = int_part.trim_start_matches('0');
+    let int_pad = (4 - int_part.len() % 4) % 4;
+    let frac_pad = (4 - frac_part.len() % 4) % 4;
+    let digits = format!("{}{}{}{}", "0".repeat(int_pad), int_part, frac_part, "0".repeat(frac_pad));
+    let mut groups: Vec<u16> = digits
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| chunk.iter().fold(0, |acc, d| acc * 10 + (d - b'0') as u16))
+        .collect();
+    let mut weight = ((int_pad + int_part.len()) / 4) as i32 - 1;
+
+    let leading = groups.iter().take_while(|&&g| g == 0).count();
+    groups.drain(..leading);
+    weight -= leading as i32;
+    while groups.last() == Some(&0) {
+        groups.pop();
+    }
+    if weight > i16::MAX as i32 {
+        return Err(format!("numeric value {} is out of range", text).into());
+    }
+
+    // Zero is always positive, with weight 0
+    let (sign, weight) = if groups.is_empty() {
+        (0x0000, 0)
+    } else if negative {
+        (0x4000, weight)
+    } else {
+        (0x0000, weight)
+    };
+    header(out, groups.len(), weight, sign, dscale);
+    for group in groups {
+        out.extend_from_slice(&group.to_be_bytes());
+    }
+    Ok(postgres_types::IsNull::No)
+}
+
+/// PostgreSQL INTERVAL, kept as its three independent components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub microseconds: i64,
+}
+
+impl Interval {
+    /// Total days for `timedelta`, counting a month as 30 days like the server does
+    fn approximate_days(&self) -> i64 {
+        self.months as i64 * 30 + self.days as i64
+    }
+
+    /// Format as an ISO 8601 duration, matching `IntervalStyle = iso_8601`
+    pub fn to_iso8601(self) -> String {
+        if self.months == 0 && self.days == 0 && self.microseconds == 0 {
+            return "PT0S".to_string();
+        }
+
+        let mut out = String::from("P");
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            out.push_str(&format!("{}Y", years));
+        }
+        if months != 0 {
+            out.push_str(&format!("{}M", months));
+        }
+        if self.days != 0 {
+            out.push_str(&format!("{}D", self.days));
+        }
+
+        if self.microseconds != 0 {
+            let hours = self.microseconds / 3_600_000_000;
+            let minutes = self.microseconds / 60_000_000 % 60;
+            let seconds = self.microseconds % 60_000_000;
+            out.push('T');
+            if hours != 0 {
+                out.push_str(&format!("{}H", hours));
+            }
+            if minutes != 0 {
+                out.push_str(&format!("{}M", minutes));
+            }
+            if seconds != 0 {
+                let sign = if seconds < 0 { "-" } else { "" };
+                let (whole, fraction) = (seconds.abs() / 1_000_000, seconds.abs() % 1_000_000);
+                if fraction == 0 {
+                    out.push_str(&format!("{}{}S", sign, whole));
+                } else {
+                    let fraction = format!("{:06}", fraction);
+                    out.push_str(&format!("{}{}.{}S", sign, whole, fraction.trim_end_matches('0')));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl<'a> FromSql<'a> for Interval {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Binary format: 8-byte microseconds, 4-byte days, 4-byte months
+        if raw.len() != 16 {
+            return Err(format!("invalid interval length: {}", raw.len()).into());
+        }
+        Ok(Interval {
+            microseconds: i64::from_be_bytes(raw[0..8].try_into()?),
+            days: i32::from_be_bytes(raw[8..12].try_into()?),
+            months: i32::from_be_bytes(raw[12..16].try_into()?),
+        })
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        *ty == postgres_types::Type::INTERVAL
+    }
+}
+
+/// A DATE, TIMESTAMP or TIMESTAMPTZ value, including PostgreSQL's
+/// `infinity` and `-infinity`
+///
+/// Python has no infinite dates, so the sentinels map to and from the
+/// `max`/`min` of `datetime.date` and `datetime.datetime`, the same
+/// convention as asyncpg. Finite values outside Python's year range 1-9999
+/// fail to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temporal<T> {
+    Value(T),
+    Infinity,
+    NegInfinity,
+}
+
+// 2000-01-01, the PostgreSQL epoch
+fn pg_epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()
+}
+
+/// Largest `datetime.datetime`, bound as and decoded from `infinity`
+/// (its date is `datetime.date.max`)
+fn py_datetime_max() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(9999, 12, 31).unwrap().and_hms_micro_opt(23, 59, 59, 999_999).unwrap()
+}
+
+/// Smallest `datetime.datetime`, bound as and decoded from `-infinity`
+/// (its date is `datetime.date.min`)
+fn py_datetime_min() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+}
+
+impl<T: PartialEq> Temporal<T> {
+    /// Wrap a value, turning Python's extreme values into the infinities
+    pub fn from_bounded(value: T, min: T, max: T) -> Self {
+        if value == max {
+            Temporal::Infinity
+        } else if value == min {
+            Temporal::NegInfinity
+        } else {
+            Temporal::Value(value)
+        }
+    }
+
+    /// Text form, with the infinities as `infinity`/`-infinity`
+    pub fn text(self, format: impl FnOnce(T) -> String) -> String {
+        match self {
+            Temporal::Value(value) => format(value),
+            Temporal::Infinity => "infinity".to_string(),
+            Temporal::NegInfinity => "-infinity".to_string(),
+        }
+    }
+
+    /// The plain value, with the infinities mapped back to `min`/`max`
+    pub fn bounded(self, min: T, max: T) -> T {
+        match self {
+            Temporal::Value(value) => value,
+            Temporal::Infinity => max,
+            Temporal::NegInfinity => min,
+        }
+    }
+}
+
+impl<'a> FromSql<'a> for Temporal<NaiveDateTime> {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Microseconds since the epoch; i64::MAX/MIN are the infinities
+        let micros = i64::from_be_bytes(raw.try_into().map_err(|_| "invalid timestamp length")?);
+        Ok(match micros {
+            i64::MAX => Temporal::Infinity,
+            i64::MIN => Temporal::NegInfinity,
+            _ => Temporal::Value(
+                pg_epoch()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    .checked_add_signed(chrono::Duration::microseconds(micros))
+                    .filter(|dt| (1..=9999).contains(&dt.year()))
+                    .ok_or("timestamp out of range for Python datetime")?,
+            ),
+        })
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        *ty == postgres_types::Type::TIMESTAMP || *ty == postgres_types::Type::TIMESTAMPTZ
+    }
+}
+
+impl<'a> FromSql<'a> for Temporal<NaiveDate> {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Days since the epoch; i32::MAX/MIN are the infinities
+        let days = i32::from_be_bytes(raw.try_into().map_err(|_| "invalid date length")?);
+        Ok(match days {
+            i32::MAX => Temporal::Infinity,
+            i32::MIN => Temporal::NegInfinity,
+            _ => Temporal::Value(
+                pg_epoch()
+                    .checked_add_signed(chrono::Duration::days(days as i64))
+                    .filter(|date| (1..=9999).contains(&date.year()))
+                    .ok_or("date out of range for Python date")?,
+            ),
+        })
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        *ty == postgres_types::Type::DATE
+    }
+}
+
+impl<T: ToSql> ToSql for Temporal<T> {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        let date = *ty == postgres_types::Type::DATE;
+        match self {
+            Temporal::Value(value) => return value.to_sql(ty, out),
+            Temporal::Infinity if date => out.extend_from_slice(&i32::MAX.to_be_bytes()),
+            Temporal::NegInfinity if date => out.extend_from_slice(&i32::MIN.to_be_bytes()),
+            Temporal::Infinity => out.extend_from_slice(&i64::MAX.to_be_bytes()),
+            Temporal::NegInfinity => out.extend_from_slice(&i64::MIN.to_be_bytes()),
+        }
+        Ok(postgres_types::IsNull::No)
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        T::accepts(ty)
     }
 
     postgres_types::to_sql_checked!();
 }
 
+/// Number of dimensions of an array value, read from its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrayDims(pub i32);
+
+impl<'a> FromSql<'a> for ArrayDims {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Header: ndim, has-null flag, element OID, then one (length, lower bound) per dimension
+        let ndim = raw.get(..4).ok_or("invalid array header")?;
+        Ok(ArrayDims(i32::from_be_bytes([ndim[0], ndim[1], ndim[2], ndim[3]])))
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        matches!(ty.kind(), postgres_types::Kind::Array(_))
+    }
+}
+
+/// Label of a user-defined enum value
+///
+/// Enum types get a dynamic OID per database, so they are matched by kind
+/// rather than by a fixed `Type` constant. Arrays of enums decode through
+/// `Vec<Option<EnumLabel>>`, which resolves the element type the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumLabel(pub String);
+
+impl<'a> FromSql<'a> for EnumLabel {
+    fn from_sql(
+        _ty: &postgres_types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Binary format is the label text itself
+        Ok(EnumLabel(std::str::from_utf8(raw)?.to_string()))
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        matches!(ty.kind(), postgres_types::Kind::Enum(_))
+    }
+}
+
 /// Convert Python object to PostgresValue with proper type handling
 pub fn py_to_postgres_value(py: Python, obj: &PyObject) -> PyResult<PostgresValue> {
     let obj_ref = obj.as_ref(py);
@@ -90,14 +918,7 @@ pub fn py_to_postgres_value(py: Python, obj: &PyObject) -> PyResult<PostgresValu
 
     // Integers - use appropriate size
     if let Ok(i) = obj_ref.downcast::<PyInt>() {
-        let val = i.extract::<i64>()?;
-        return if val >= i16::MIN as i64 && val <= i16::MAX as i64 {
-            Ok(PostgresValue::Int16(val as i16))
-        } else if val >= i32::MIN as i64 && val <= i32::MAX as i64 {
-            Ok(PostgresValue::Int32(val as i32))
-        } else {
-            Ok(PostgresValue::Int64(val))
-        };
+        return py_int_value(i);
     }
 
     // Floats - use native float types
@@ -112,6 +933,26 @@ pub fn py_to_postgres_value(py: Python, obj: &PyObject) -> PyResult<PostgresValu
         return Ok(PostgresValue::String(s.extract()?));
     }
 
+    // UUIDs - bind the 16 raw bytes as the native uuid type
+    if let Some(uuid) = extract_uuid(py, obj_ref)? {
+        return Ok(PostgresValue::Uuid(uuid.into_bytes()));
+    }
+
+    // bytes/bytearray - bind as BYTEA, or as the raw bytes of a UUID parameter
+    if let Some(bytes) = extract_bytes(obj_ref) {
+        return Ok(PostgresValue::Bytes(bytes));
+    }
+
+    // Decimals - bind exactly as NUMERIC
+    if let Some(decimal) = extract_decimal(py, obj_ref)? {
+        return Ok(PostgresValue::Numeric(decimal));
+    }
+
+    // Dicts - serialize as JSON
+    if obj_ref.is_instance_of::<PyDict>() {
+        return Ok(PostgresValue::Json(py_to_json(obj_ref)?));
+    }
+
     // Fallback: convert to string representation
     let s = obj_ref.str()?.extract::<String>()?;
     Ok(PostgresValue::String(s))
@@ -123,38 +964,39 @@ pub fn postgres_to_py(
     row: &tokio_postgres::Row,
     idx: usize,
     col_type: &postgres_types::Type,
+    options: ConversionOptions,
 ) -> PyResult<PyObject> {
     // Type-specialized conversion for performance
     match *col_type {
         postgres_types::Type::INT2 => match row.try_get::<_, Option<i16>>(idx) {
             Ok(Some(i)) => Ok(i.into_py(py)),
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
         postgres_types::Type::INT4 => match row.try_get::<_, Option<i32>>(idx) {
             Ok(Some(i)) => Ok(i.into_py(py)),
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
         postgres_types::Type::INT8 => match row.try_get::<_, Option<i64>>(idx) {
             Ok(Some(i)) => Ok(i.into_py(py)),
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
         postgres_types::Type::FLOAT4 => match row.try_get::<_, Option<f32>>(idx) {
             Ok(Some(f)) => Ok(f.into_py(py)),
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
         postgres_types::Type::FLOAT8 => match row.try_get::<_, Option<f64>>(idx) {
             Ok(Some(f)) => Ok(f.into_py(py)),
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
         postgres_types::Type::BOOL => match row.try_get::<_, Option<bool>>(idx) {
             Ok(Some(b)) => Ok(b.into_py(py)),
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
         postgres_types::Type::TEXT
         | postgres_types::Type::VARCHAR
@@ -165,51 +1007,409 @@ pub fn postgres_to_py(
                 Ok(interned.as_str().into_py(py))
             }
             Ok(None) => Ok(py.None()),
-            Err(_) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
         },
-        _ => {
-            // Generic fallback for other types
-            if let Ok(Some(s)) = row.try_get::<_, Option<String>>(idx) {
-                Ok(s.into_py(py))
-            } else {
-                Ok(py.None())
+        postgres_types::Type::BOOL_ARRAY => array_to_py(py, row, idx, options, |b: bool| Ok(b.into_py(py))),
+        postgres_types::Type::INT2_ARRAY => array_to_py(py, row, idx, options, |i: i16| Ok(i.into_py(py))),
+        postgres_types::Type::INT4_ARRAY => array_to_py(py, row, idx, options, |i: i32| Ok(i.into_py(py))),
+        postgres_types::Type::INT8_ARRAY => array_to_py(py, row, idx, options, |i: i64| Ok(i.into_py(py))),
+        postgres_types::Type::FLOAT4_ARRAY => array_to_py(py, row, idx, options, |f: f32| Ok(f.into_py(py))),
+        postgres_types::Type::FLOAT8_ARRAY => array_to_py(py, row, idx, options, |f: f64| Ok(f.into_py(py))),
+        postgres_types::Type::TEXT_ARRAY
+        | postgres_types::Type::VARCHAR_ARRAY
+        | postgres_types::Type::BPCHAR_ARRAY => array_to_py(py, row, idx, options, |s: &str| Ok(s.into_py(py))),
+        postgres_types::Type::UUID => match row.try_get::<_, Option<Uuid>>(idx) {
+            Ok(Some(uuid)) => uuid_to_py(py, &uuid),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::UUID_ARRAY => array_to_py(py, row, idx, options, |uuid: Uuid| uuid_to_py(py, &uuid)),
+        postgres_types::Type::NUMERIC => match row.try_get::<_, Option<NumericText>>(idx) {
+            Ok(Some(numeric)) => numeric_to_py(py, &numeric.0, options),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::NUMERIC_ARRAY => {
+            array_to_py(py, row, idx, options, |numeric: NumericText| numeric_to_py(py, &numeric.0, options))
+        }
+        postgres_types::Type::TIMESTAMP | postgres_types::Type::TIMESTAMPTZ => {
+            match row.try_get::<_, Option<Temporal<NaiveDateTime>>>(idx) {
+                Ok(Some(dt)) => datetime_to_py(
+                    py,
+                    dt.bounded(py_datetime_min(), py_datetime_max()),
+                    *col_type == postgres_types::Type::TIMESTAMPTZ,
+                    options.timezone,
+                ),
+                Ok(None) => Ok(py.None()),
+                Err(e) => decode_failure(py, options, e),
             }
         }
-    }
-}
-
-/// Convert Python objects to Box<dyn ToSql> with proper type handling
-pub fn py_objects_to_postgres_values(
-    py: Python,
-    objects: &[PyObject],
-) -> PyResult<Vec<Box<dyn postgres_types::ToSql + Sync + Send>>> {
-    let mut values: Vec<Box<dyn postgres_types::ToSql + Sync + Send>> =
-        Vec::with_capacity(objects.len());
-
-    for obj in objects {
+        postgres_types::Type::DATE => match row.try_get::<_, Option<Temporal<NaiveDate>>>(idx) {
+            Ok(Some(date)) => date_to_py(py, date.bounded(py_datetime_min().date(), py_datetime_max().date())),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::TIME => match row.try_get::<_, Option<NaiveTime>>(idx) {
+            Ok(Some(time)) => time_to_py(py, time),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::INTERVAL => match row.try_get::<_, Option<Interval>>(idx) {
+            Ok(Some(interval)) if options.interval_iso => Ok(interval.to_iso8601().into_py(py)),
+            Ok(Some(interval)) => {
+                let timedelta = cached_type(py, &TIMEDELTA_TYPE, "datetime", "timedelta")?;
+                Ok(timedelta
+                    .call1((interval.approximate_days(), 0, interval.microseconds))?
+                    .into())
+            }
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::JSON | postgres_types::Type::JSONB => {
+            match row.try_get::<_, Option<JsonText>>(idx) {
+                Ok(Some(json)) if options.text_types.includes(col_type) => Ok(json.0.into_py(py)),
+                Ok(Some(json)) => json_to_py(py, &json.0),
+                Ok(None) => Ok(py.None()),
+                Err(e) => decode_failure(py, options, e),
+            }
+        }
+        postgres_types::Type::JSON_ARRAY | postgres_types::Type::JSONB_ARRAY => {
+            let as_text = options.text_types.includes(col_type);
+            array_to_py(py, row, idx, options, |json: JsonText| {
+                if as_text {
+                    Ok(json.0.into_py(py))
+                } else {
+                    json_to_py(py, &json.0)
+                }
+            })
+        }
+        postgres_types::Type::BYTEA if options.text_types.bytea => match row.try_get::<_, Option<&[u8]>>(idx) {
+            Ok(Some(bytes)) => Ok(format!("\\x{}", hex::encode(bytes)).into_py(py)),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::BYTEA_ARRAY if options.text_types.bytea => array_to_py(py, row, idx, options, |b: &[u8]| {
+            Ok(format!("\\x{}", hex::encode(b)).into_py(py))
+        }),
+        postgres_types::Type::BYTEA => match row.try_get::<_, Option<&[u8]>>(idx) {
+            Ok(Some(bytes)) => Ok(PyBytes::new(py, bytes).into()),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        postgres_types::Type::BYTEA_ARRAY => {
+            array_to_py(py, row, idx, options, |b: &[u8]| Ok(PyBytes::new(py, b).into()))
+        }
+        postgres_types::Type::TID => match row.try_get::<_, Option<Tid>>(idx) {
+            Ok(Some(tid)) => Ok((tid.block, tid.offset).into_py(py)),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        _ if EnumLabel::accepts(col_type) => match row.try_get::<_, Option<EnumLabel>>(idx) {
+            Ok(Some(label)) => Ok(intern_string(label.0).as_str().into_py(py)),
+            Ok(None) => Ok(py.None()),
+            Err(e) => decode_failure(py, options, e),
+        },
+        _ if <Vec<Option<EnumLabel>>>::accepts(col_type) => {
+            array_to_py(py, row, idx, options, |label: EnumLabel| Ok(label.0.into_py(py)))
+        }
+        _ if crate::composite::is_registered(col_type) => {
+            crate::composite::composite_to_py(py, row, idx, col_type, options)
+        }
+        _ => {
+            // Generic fallback for other types
+            match row.try_get::<_, Option<String>>(idx) {
+                Ok(Some(s)) => Ok(s.into_py(py)),
+                Ok(None) => Ok(py.None()),
+                Err(e) => decode_failure(py, options, e),
+            }
+        }
+    }
+}
+
+/// Decode a one-dimensional array column into a list, converting each
+/// non-NULL element with `element`; NULL elements become None
+///
+/// Multi-dimensional arrays raise NotSupportedError instead of being
+/// flattened or silently returned as None.
+fn array_to_py<'a, T: FromSql<'a>>(
+    py: Python,
+    row: &'a tokio_postgres::Row,
+    idx: usize,
+    options: ConversionOptions,
+    element: impl Fn(T) -> PyResult<PyObject>,
+) -> PyResult<PyObject> {
+    if let Ok(Some(ArrayDims(dims))) = row.try_get::<_, Option<ArrayDims>>(idx) {
+        if dims > 1 {
+            return Err(not_supported_error(&format!("{}-dimensional arrays", dims)));
+        }
+    }
+
+    match row.try_get::<_, Option<Vec<Option<T>>>>(idx) {
+        Ok(Some(values)) => {
+            // Build the list in array order; elements are never reordered
+            let list = PyList::empty(py);
+            for value in values {
+                match value {
+                    Some(value) => list.append(element(value)?)?,
+                    None => list.append(py.None())?,
+                }
+            }
+            Ok(list.into())
+        }
+        Ok(None) => Ok(py.None()),
+        Err(e) => decode_failure(py, options, e),
+    }
+}
+
+/// `array.array` typecode holding a column of the given numeric type, or
+/// None for types that don't have one
+///
+/// The codes have the same width as the PostgreSQL type on every platform
+/// CPython supports (`'l'` is avoided, as it is 32-bit on Windows).
+fn column_typecode(ty: &postgres_types::Type) -> Option<&'static str> {
+    match *ty {
+        postgres_types::Type::INT2 => Some("h"),
+        postgres_types::Type::INT4 => Some("i"),
+        postgres_types::Type::INT8 => Some("q"),
+        postgres_types::Type::FLOAT4 => Some("f"),
+        postgres_types::Type::FLOAT8 => Some("d"),
+        _ => None,
+    }
+}
+
+/// Native-endian bytes of every value in a column, or None if any is NULL
+fn column_bytes<'a, T: FromSql<'a>, const N: usize>(
+    rows: &'a [tokio_postgres::Row],
+    idx: usize,
+    to_bytes: fn(T) -> [u8; N],
+) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(rows.len() * N);
+    for row in rows {
+        out.extend_from_slice(&to_bytes(row.try_get::<_, Option<T>>(idx).ok()??));
+    }
+    Some(out)
+}
+
+/// Decode one column of a result into a single Python object
+///
+/// Integer and floating point columns without NULLs become an `array.array`
+/// of the matching width (int2 `'h'`, int4 `'i'`, int8 `'q'`, real `'f'`,
+/// double precision `'d'`), which numpy can wrap without copying via
+/// `numpy.frombuffer`. Other columns, and numeric columns containing a
+/// NULL, become a list of the values `postgres_to_py` gives.
+pub fn column_to_py(
+    py: Python,
+    rows: &[tokio_postgres::Row],
+    idx: usize,
+    ty: &postgres_types::Type,
+    options: ConversionOptions,
+) -> PyResult<PyObject> {
+    if let Some(typecode) = column_typecode(ty) {
+        let bytes = match *ty {
+            postgres_types::Type::INT2 => column_bytes(rows, idx, i16::to_ne_bytes),
+            postgres_types::Type::INT4 => column_bytes(rows, idx, i32::to_ne_bytes),
+            postgres_types::Type::INT8 => column_bytes(rows, idx, i64::to_ne_bytes),
+            postgres_types::Type::FLOAT4 => column_bytes(rows, idx, f32::to_ne_bytes),
+            _ => column_bytes(rows, idx, f64::to_ne_bytes),
+        };
+        if let Some(bytes) = bytes {
+            let array = cached_type(py, &ARRAY_TYPE, "array", "array")?;
+            return Ok(array.call1((typecode, PyBytes::new(py, &bytes)))?.into());
+        }
+    }
+
+    let list = PyList::empty(py);
+    for row in rows {
+        list.append(postgres_to_py(py, row, idx, ty, options)?)?;
+    }
+    Ok(list.into())
+}
+
+/// Turn a column decoded by `column_to_py` into data for a pandas column
+///
+/// Typed arrays are wrapped by numpy without copying. Integer and boolean
+/// lists with NULLs get pandas' nullable dtypes, and floating point ones a
+/// float array with NaN for NULL. Other lists are returned as they are.
+pub fn column_to_pandas(
+    py: Python,
+    pandas: &PyAny,
+    numpy: &PyAny,
+    column: &PyAny,
+    ty: &postgres_types::Type,
+) -> PyResult<PyObject> {
+    let list = match column.downcast::<PyList>() {
+        Ok(list) => list,
+        Err(_) => {
+            let kwargs = [("dtype", column.getattr("typecode")?)].into_py_dict(py);
+            return Ok(numpy.call_method("frombuffer", (column,), Some(kwargs))?.into());
+        }
+    };
+
+    let (module, dtype) = match *ty {
+        postgres_types::Type::FLOAT4 => (numpy, "float32"),
+        postgres_types::Type::FLOAT8 => (numpy, "float64"),
+        postgres_types::Type::INT2 => (pandas, "Int16"),
+        postgres_types::Type::INT4 => (pandas, "Int32"),
+        postgres_types::Type::INT8 => (pandas, "Int64"),
+        postgres_types::Type::BOOL if list.iter().any(|value| value.is_none()) => (pandas, "boolean"),
+        _ => return Ok(list.into()),
+    };
+    let kwargs = [("dtype", dtype)].into_py_dict(py);
+    Ok(module.call_method("array", (list,), Some(kwargs))?.into())
+}
+
+/// Result for a column value that couldn't be decoded: None, or DataError in strict mode
+pub fn decode_failure(py: Python, options: ConversionOptions, error: tokio_postgres::Error) -> PyResult<PyObject> {
+    if options.strict {
+        Err(DataError::new_err(error.to_string()))
+    } else {
+        Ok(py.None())
+    }
+}
+
+/// Decode a non-NULL binary value as `T`, checking that `T` accepts the type
+pub fn decode_raw<'a, T: FromSql<'a>>(
+    ty: &postgres_types::Type,
+    raw: &'a [u8],
+) -> Result<T, Box<dyn std::error::Error + Sync + Send>> {
+    if !T::accepts(ty) {
+        return Err(format!("cannot decode a value of type {}", ty).into());
+    }
+    T::from_sql(ty, raw)
+}
+
+/// Decode a single value from its binary form, such as a composite field
+///
+/// Covers the scalar types `postgres_to_py` handles, and nested registered
+/// composites. Other types decode as text where they can; anything else is
+/// a decode failure (None, or DataError in strict mode).
+pub fn field_to_py(
+    py: Python,
+    ty: &postgres_types::Type,
+    raw: Option<&[u8]>,
+    options: ConversionOptions,
+) -> PyResult<PyObject> {
+    use decode_raw as decode;
+
+    let raw = match raw {
+        Some(raw) => raw,
+        None => return Ok(py.None()),
+    };
+    let decoded = match *ty {
+        postgres_types::Type::INT2 => decode::<i16>(ty, raw).map(|i| Ok(i.into_py(py))),
+        postgres_types::Type::INT4 => decode::<i32>(ty, raw).map(|i| Ok(i.into_py(py))),
+        postgres_types::Type::INT8 => decode::<i64>(ty, raw).map(|i| Ok(i.into_py(py))),
+        postgres_types::Type::FLOAT4 => decode::<f32>(ty, raw).map(|f| Ok(f.into_py(py))),
+        postgres_types::Type::FLOAT8 => decode::<f64>(ty, raw).map(|f| Ok(f.into_py(py))),
+        postgres_types::Type::BOOL => decode::<bool>(ty, raw).map(|b| Ok(b.into_py(py))),
+        postgres_types::Type::UUID => decode::<Uuid>(ty, raw).map(|uuid| uuid_to_py(py, &uuid)),
+        postgres_types::Type::NUMERIC => decode::<NumericText>(ty, raw).map(|numeric| numeric_to_py(py, &numeric.0, options)),
+        postgres_types::Type::TIMESTAMP | postgres_types::Type::TIMESTAMPTZ => {
+            decode::<Temporal<NaiveDateTime>>(ty, raw).map(|dt| {
+                datetime_to_py(
+                    py,
+                    dt.bounded(py_datetime_min(), py_datetime_max()),
+                    *ty == postgres_types::Type::TIMESTAMPTZ,
+                    options.timezone,
+                )
+            })
+        }
+        postgres_types::Type::DATE => decode::<Temporal<NaiveDate>>(ty, raw)
+            .map(|date| date_to_py(py, date.bounded(py_datetime_min().date(), py_datetime_max().date()))),
+        postgres_types::Type::TIME => decode::<NaiveTime>(ty, raw).map(|time| time_to_py(py, time)),
+        postgres_types::Type::INTERVAL => decode::<Interval>(ty, raw).map(|interval| {
+            if options.interval_iso {
+                return Ok(interval.to_iso8601().into_py(py));
+            }
+            let timedelta = cached_type(py, &TIMEDELTA_TYPE, "datetime", "timedelta")?;
+            Ok(timedelta.call1((interval.approximate_days(), 0, interval.microseconds))?.into())
+        }),
+        postgres_types::Type::JSON | postgres_types::Type::JSONB => decode::<JsonText>(ty, raw).map(|json| {
+            if options.text_types.includes(ty) {
+                Ok(json.0.into_py(py))
+            } else {
+                json_to_py(py, &json.0)
+            }
+        }),
+        postgres_types::Type::BYTEA if options.text_types.bytea => {
+            Ok(Ok(format!("\\x{}", hex::encode(raw)).into_py(py)))
+        }
+        postgres_types::Type::BYTEA => Ok(Ok(PyBytes::new(py, raw).into())),
+        _ if EnumLabel::accepts(ty) => decode::<EnumLabel>(ty, raw).map(|label| Ok(label.0.into_py(py))),
+        _ if crate::composite::is_registered(ty) => crate::composite::composite_value_to_py(py, ty, raw, options),
+        _ => decode::<String>(ty, raw).map(|s| Ok(s.into_py(py))),
+    };
+
+    match decoded {
+        Ok(value) => value,
+        Err(e) if options.strict => Err(DataError::new_err(e.to_string())),
+        Err(_) => Ok(py.None()),
+    }
+}
+
+/// Convert Python objects to Box<dyn ToSql> with proper type handling
+pub fn py_objects_to_postgres_values(
+    py: Python,
+    objects: &[PyObject],
+    options: ConversionOptions,
+) -> PyResult<Vec<Box<dyn postgres_types::ToSql + Sync + Send>>> {
+    let mut values: Vec<Box<dyn postgres_types::ToSql + Sync + Send>> =
+        Vec::with_capacity(objects.len());
+
+    for obj in objects {
         let obj_ref = obj.as_ref(py);
 
         if obj.is_none(py) {
-            values.push(Box::new(None::<String>));
+            values.push(Box::new(PostgresValue::Null));
         } else if let Ok(b) = obj_ref.downcast::<PyBool>() {
             // Use native boolean type
             let bool_val: bool = b.extract()?;
             values.push(Box::new(bool_val));
         } else if let Ok(i) = obj_ref.downcast::<PyInt>() {
-            // Use appropriate native integer type
-            let val = i.extract::<i64>()?;
-            if val >= i32::MIN as i64 && val <= i32::MAX as i64 {
-                values.push(Box::new(val as i32));
-            } else {
-                values.push(Box::new(val));
-            }
+            // Sized to the value, encoded as whatever the parameter needs
+            values.push(Box::new(py_int_value(i)?));
         } else if let Ok(f) = obj_ref.downcast::<PyFloat>() {
-            // Use f32 for PostgreSQL REAL type compatibility
-            let val = f.value() as f32;
-            values.push(Box::new(val));
+            // Full double precision, narrowed only for REAL parameters
+            values.push(Box::new(FloatParam { value: f.value(), strict: options.strict }));
         } else if let Ok(s) = obj_ref.downcast::<PyString>() {
-            let s: String = s.extract()?;
-            values.push(Box::new(s));
+            // Text, or the JSON document/path it holds for JSON parameters
+            if s.len()? < LARGE_TEXT_LEN {
+                values.push(Box::new(PostgresValue::String(s.extract()?)));
+            } else {
+                values.push(Box::new(TextParam::new(s)?));
+            }
+        } else if obj_ref.is_instance_of::<PyList>() {
+            // Lists that can't form an array (nested, mixed types) must be valid JSON
+            let array = extract_array(py, obj_ref, options)?;
+            let json = match array {
+                Some(_) => py_to_json(obj_ref).ok(),
+                None => Some(py_to_json(obj_ref)?),
+            };
+            values.push(Box::new(ListParam { array, json }));
+        } else if obj_ref.is_instance_of::<PyDict>() {
+            values.push(Box::new(PostgresValue::Json(py_to_json(obj_ref)?)));
+        } else if let Some(bytes) = extract_bytes(obj_ref) {
+            values.push(Box::new(PostgresValue::Bytes(bytes)));
+        } else if let Some(uuid) = extract_uuid(py, obj_ref)? {
+            values.push(Box::new(PostgresValue::Uuid(uuid.into_bytes())));
+        } else if let Some(decimal) = extract_decimal(py, obj_ref)? {
+            values.push(Box::new(PostgresValue::Numeric(decimal)));
+        } else if obj_ref.is_instance(cached_type(py, &DATETIME_TYPE, "datetime", "datetime")?)? {
+            // Naive datetimes bind as TIMESTAMP, aware ones as TIMESTAMPTZ;
+            // datetime.max/min bind as infinity/-infinity
+            let (min, max) = (py_datetime_min(), py_datetime_max());
+            match extract_datetime(obj_ref)? {
+                PyDateTimeValue::Naive(dt) => values.push(Box::new(Temporal::from_bounded(dt, min, max))),
+                PyDateTimeValue::Aware(dt) => values.push(Box::new(Temporal::from_bounded(
+                    dt,
+                    DateTime::from_naive_utc_and_offset(min, Utc),
+                    DateTime::from_naive_utc_and_offset(max, Utc),
+                ))),
+            }
+        } else if let Some(date) = extract_date(py, obj_ref)? {
+            values.push(Box::new(Temporal::from_bounded(date, py_datetime_min().date(), py_datetime_max().date())));
+        } else if let Some(time) = extract_time(py, obj_ref)? {
+            values.push(Box::new(time));
         } else {
             let s = obj_ref.str()?.extract::<String>()?;
             values.push(Box::new(s));
@@ -218,6 +1418,464 @@ pub fn py_objects_to_postgres_values(
     Ok(values)
 }
 
+/// A parameter sent as untyped text in the text format, so the server infers
+/// its type from the query
+///
+/// Parameters of the unnamed statement are declared along with the query
+/// instead of being described first. Leaving them untyped resolves them the
+/// same way as preparing the query would.
+#[derive(Debug)]
+pub struct UntypedText(Option<String>);
+
+impl ToSql for UntypedText {
+    fn to_sql(
+        &self,
+        _ty: &postgres_types::Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        match &self.0 {
+            Some(text) => {
+                out.extend_from_slice(text.as_bytes());
+                Ok(postgres_types::IsNull::No)
+            }
+            None => Ok(postgres_types::IsNull::Yes),
+        }
+    }
+
+    fn accepts(_ty: &postgres_types::Type) -> bool {
+        true
+    }
+
+    fn encode_format(&self, _ty: &postgres_types::Type) -> postgres_types::Format {
+        postgres_types::Format::Text
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// Convert Python objects into untyped text parameters
+///
+/// Follows the same rules as `py_objects_to_postgres_values`, writing each
+/// value in PostgreSQL's text form. Returns None if any value only binds
+/// correctly once its parameter type is known: lists (the array element
+/// type) and, in strict mode, floats (checked against REAL).
+pub fn py_objects_to_untyped_text(
+    py: Python,
+    objects: &[PyObject],
+    options: ConversionOptions,
+) -> PyResult<Option<Vec<UntypedText>>> {
+    let mut values = Vec::with_capacity(objects.len());
+    for obj in objects {
+        match py_to_untyped_text(py, obj.as_ref(py), options)? {
+            Some(text) => values.push(UntypedText(text)),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(values))
+}
+
+/// Text form of one parameter: Some(None) for NULL, None if it has none
+fn py_to_untyped_text(py: Python, obj: &PyAny, options: ConversionOptions) -> PyResult<Option<Option<String>>> {
+    let text = if obj.is_none() {
+        return Ok(Some(None));
+    } else if let Ok(b) = obj.downcast::<PyBool>() {
+        if b.is_true() { "t" } else { "f" }.to_string()
+    } else if let Ok(i) = obj.downcast::<PyInt>() {
+        i.str()?.extract()?
+    } else if let Ok(f) = obj.downcast::<PyFloat>() {
+        if options.strict {
+            return Ok(None);
+        }
+        float_text(f.value())
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        s.extract()?
+    } else if obj.is_instance_of::<PyList>() {
+        return Ok(None);
+    } else if obj.is_instance_of::<PyDict>() {
+        py_to_json(obj)?
+    } else if let Some(bytes) = extract_bytes(obj) {
+        format!("\\x{}", hex::encode(bytes))
+    } else if let Some(uuid) = extract_uuid(py, obj)? {
+        uuid.hyphenated().to_string()
+    } else if let Some(decimal) = extract_decimal(py, obj)? {
+        decimal
+    } else if obj.is_instance(cached_type(py, &DATETIME_TYPE, "datetime", "datetime")?)? {
+        let (min, max) = (py_datetime_min(), py_datetime_max());
+        match extract_datetime(obj)? {
+            PyDateTimeValue::Naive(dt) => {
+                Temporal::from_bounded(dt, min, max).text(|dt| dt.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+            }
+            PyDateTimeValue::Aware(dt) => Temporal::from_bounded(dt.naive_utc(), min, max)
+                .text(|dt| dt.format("%Y-%m-%d %H:%M:%S%.f+00").to_string()),
+        }
+    } else if let Some(date) = extract_date(py, obj)? {
+        Temporal::from_bounded(date, py_datetime_min().date(), py_datetime_max().date())
+            .text(|date| date.format("%Y-%m-%d").to_string())
+    } else if let Some(time) = extract_time(py, obj)? {
+        time.format("%H:%M:%S%.f").to_string()
+    } else {
+        obj.str()?.extract()?
+    };
+    Ok(Some(Some(text)))
+}
+
+/// Text form of a double that reads back as the same value
+fn float_text(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        // Rust prints the shortest digits that round-trip
+        format!("{:?}", value)
+    }
+}
+
+/// Convert a Python int; ints beyond 64 bits keep their exact decimal text
+fn py_int_value(i: &PyInt) -> PyResult<PostgresValue> {
+    match i.extract::<i64>() {
+        Ok(val) => Ok(int_value(val)),
+        Err(_) => Ok(PostgresValue::Numeric(i.str()?.extract()?)),
+    }
+}
+
+/// The smallest integer variant that holds `val`
+fn int_value(val: i64) -> PostgresValue {
+    if let Ok(v) = i16::try_from(val) {
+        PostgresValue::Int16(v)
+    } else if let Ok(v) = i32::try_from(val) {
+        PostgresValue::Int32(v)
+    } else {
+        PostgresValue::Int64(val)
+    }
+}
+
+/// Write an integer in the binary format of the parameter's type
+///
+/// The parameter type comes from the server, so `5` fills a smallint,
+/// integer, bigint, oid, real, double precision or numeric parameter alike.
+/// Values that don't fit the type are an error rather than being truncated.
+fn write_int(
+    val: i64,
+    ty: &postgres_types::Type,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    fn narrow<T: TryFrom<i64>>(val: i64, name: &str) -> Result<T, ConversionError> {
+        T::try_from(val).map_err(|_| ConversionError(format!("{} is out of range for type {}", val, name)))
+    }
+
+    match *ty {
+        postgres_types::Type::INT2 => narrow::<i16>(val, "smallint")?.to_sql(ty, out),
+        postgres_types::Type::INT4 => narrow::<i32>(val, "integer")?.to_sql(ty, out),
+        postgres_types::Type::OID => narrow::<u32>(val, "oid")?.to_sql(ty, out),
+        postgres_types::Type::FLOAT4 => (val as f32).to_sql(ty, out),
+        postgres_types::Type::FLOAT8 => (val as f64).to_sql(ty, out),
+        postgres_types::Type::NUMERIC => write_numeric(&val.to_string(), out),
+        // Anything else gets the usual type mismatch error
+        _ => val.to_sql_checked(ty, out),
+    }
+}
+
+/// Write bytes as a BYTEA parameter, or as a UUID parameter's 16 raw bytes
+///
+/// Keyed off the parameter type the server reports, so 16 bytes bound to a
+/// bytea column stay bytea.
+fn write_bytes(
+    val: &[u8],
+    ty: &postgres_types::Type,
+    out: &mut bytes::BytesMut,
+) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    if *ty == postgres_types::Type::UUID {
+        let uuid = Uuid::from_slice(val)
+            .map_err(|_| ConversionError(format!("uuid needs 16 bytes, got {}", val.len())))?;
+        return uuid.to_sql(ty, out);
+    }
+    val.to_sql_checked(ty, out)
+}
+
+/// Extract a Python int as i64; in strict mode, out-of-range values raise DataError
+fn extract_int(i: &PyAny, options: ConversionOptions) -> PyResult<i64> {
+    match i.extract::<i64>() {
+        Ok(val) => Ok(val),
+        Err(_) if options.strict => {
+            Err(DataError::new_err(format!("integer {} is out of range for type bigint", i)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Narrow a float to f32 for a REAL parameter; in strict mode, lossy values are an error
+fn narrow_real(value: f64, strict: bool) -> Result<f32, ConversionError> {
+    if strict && !real_is_exact(value) {
+        return Err(ConversionError(format!(
+            "float {} cannot be represented as type real without losing precision",
+            value
+        )));
+    }
+    Ok(value as f32)
+}
+
+/// A Python float, bound as double precision, or narrowed to f32 when the
+/// parameter is REAL
+#[derive(Debug)]
+struct FloatParam {
+    value: f64,
+    strict: bool,
+}
+
+impl ToSql for FloatParam {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        if *ty == postgres_types::Type::FLOAT4 {
+            narrow_real(self.value, self.strict)?.to_sql(ty, out)
+        } else {
+            self.value.to_sql_checked(ty, out)
+        }
+    }
+
+    fn accepts(_ty: &postgres_types::Type) -> bool {
+        true
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// Length in characters from which a str parameter binds as `TextParam`
+const LARGE_TEXT_LEN: usize = 64 * 1024;
+
+/// A Python str, bound straight from its UTF-8 encoding
+///
+/// Extracting a `String` copies the text once more after Python encodes it
+/// (the abi3 build has no access to the cached UTF-8 buffer), which doubles
+/// peak memory for large values. Holding the encoded bytes instead leaves a
+/// single copy, into the wire buffer. Shorter strings are cheaper to copy
+/// than to hand over, so only those of `LARGE_TEXT_LEN` characters or more
+/// bind this way.
+#[derive(Debug)]
+struct TextParam {
+    encoded: Py<PyBytes>,
+}
+
+impl TextParam {
+    fn new(s: &PyString) -> PyResult<Self> {
+        let encoded = s.call_method0(pyo3::intern!(s.py(), "encode"))?;
+        Ok(Self { encoded: encoded.downcast::<PyBytes>()?.into() })
+    }
+}
+
+impl ToSql for TextParam {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        // Reentrant on the binding thread, which already holds the GIL
+        Python::with_gil(|py| {
+            let utf8 = self.encoded.as_ref(py).as_bytes();
+            match *ty {
+                // Python's encoder only produces valid UTF-8, so plain text
+                // types take the bytes as they are
+                postgres_types::Type::TEXT
+                | postgres_types::Type::VARCHAR
+                | postgres_types::Type::BPCHAR
+                | postgres_types::Type::NAME
+                | postgres_types::Type::UNKNOWN => {
+                    out.extend_from_slice(utf8);
+                    Ok(postgres_types::IsNull::No)
+                }
+                _ => write_text(std::str::from_utf8(utf8)?, ty, out),
+            }
+        })
+    }
+
+    fn accepts(_ty: &postgres_types::Type) -> bool {
+        true
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// Convert a Python list into an array parameter
+///
+/// None elements bind as NULL array elements. The element type comes from
+/// the non-None elements: bool, uuid.UUID, decimal.Decimal, int, float (ints
+/// allowed), str or bytes/bytearray. Number lists adapt to the integer or
+/// floating point array type of the parameter. Only one-dimensional arrays
+/// are supported. Empty and all-None lists carry no element type, so they
+/// bind as an array of NULLs that fits whatever array type the server
+/// expects.
+fn extract_array(
+    py: Python,
+    obj: &PyAny,
+    options: ConversionOptions,
+) -> PyResult<Option<Box<dyn postgres_types::ToSql + Sync + Send>>> {
+    let list = match obj.downcast::<PyList>() {
+        Ok(list) => list,
+        Err(_) => return Ok(None),
+    };
+
+    if list.iter().all(|item| item.is_none()) {
+        return Ok(Some(Box::new(vec![PostgresValue::Null; list.len()])));
+    }
+
+    if let Some(flags) = extract_bool_list(obj)? {
+        return Ok(Some(Box::new(flags)));
+    }
+    if let Some(uuids) = extract_uuid_list(py, obj)? {
+        return Ok(Some(Box::new(uuids)));
+    }
+    if let Some(decimals) = extract_typed_list(obj, |item| {
+        Ok(extract_decimal(py, item)?.map(PostgresValue::Numeric))
+    })? {
+        return Ok(Some(Box::new(decimals)));
+    }
+    if let Some(ints) = extract_typed_list(obj, |item| {
+        if item.is_instance_of::<PyInt>() && !item.is_instance_of::<PyBool>() {
+            extract_int(item, options).map(Some)
+        } else {
+            Ok(None)
+        }
+    })? {
+        return Ok(Some(Box::new(NumberArray { numbers: Numbers::Ints(ints), strict: options.strict })));
+    }
+    if let Some(floats) = extract_typed_list(obj, |item| {
+        if item.is_instance_of::<PyFloat>() || (item.is_instance_of::<PyInt>() && !item.is_instance_of::<PyBool>()) {
+            item.extract::<f64>().map(Some)
+        } else {
+            Ok(None)
+        }
+    })? {
+        return Ok(Some(Box::new(NumberArray { numbers: Numbers::Floats(floats), strict: options.strict })));
+    }
+    if let Some(strings) = extract_typed_list(obj, |item| {
+        item.downcast::<PyString>().ok().map(|s| s.extract::<String>()).transpose()
+    })? {
+        return Ok(Some(Box::new(strings)));
+    }
+    if let Some(blobs) = extract_typed_list(obj, |item| Ok(extract_bytes(item)))? {
+        return Ok(Some(Box::new(blobs)));
+    }
+
+    Ok(None)
+}
+
+#[derive(Debug)]
+enum Numbers {
+    Ints(Vec<Option<i64>>),
+    Floats(Vec<Option<f64>>),
+}
+
+/// A list of Python numbers, narrowed to the array's element type at bind time
+///
+/// The element type is only known once the server reports the parameter
+/// type, so `[1, 2]` can fill an int2[], int4[], int8[], real[] or double
+/// precision[] parameter. Ints that don't fit a smaller integer type are an
+/// error; floats narrowed to REAL lose precision unless `strict` is set.
+#[derive(Debug)]
+struct NumberArray {
+    numbers: Numbers,
+    strict: bool,
+}
+
+impl ToSql for NumberArray {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        fn narrow<T: TryFrom<i64>>(ints: &[Option<i64>], name: &str) -> Result<Vec<Option<T>>, ConversionError> {
+            ints.iter()
+                .map(|v| {
+                    v.map(|v| T::try_from(v).map_err(|_| ConversionError(format!("{} is out of range for type {}", v, name))))
+                        .transpose()
+                })
+                .collect()
+        }
+
+        let reals = |floats: Vec<Option<f64>>| -> Result<Vec<Option<f32>>, ConversionError> {
+            floats.into_iter().map(|v| v.map(|v| narrow_real(v, self.strict)).transpose()).collect()
+        };
+
+        let member = match ty.kind() {
+            postgres_types::Kind::Array(member) => member.clone(),
+            _ => return Err(format!("cannot bind a list of numbers to type {}", ty).into()),
+        };
+        match (&self.numbers, member) {
+            (Numbers::Ints(ints), postgres_types::Type::INT2) => narrow::<i16>(ints, "smallint")?.to_sql(ty, out),
+            (Numbers::Ints(ints), postgres_types::Type::INT4) => narrow::<i32>(ints, "integer")?.to_sql(ty, out),
+            (Numbers::Ints(ints), postgres_types::Type::INT8) => ints.to_sql(ty, out),
+            (Numbers::Ints(ints), postgres_types::Type::FLOAT4) => {
+                reals(ints.iter().map(|v| v.map(|v| v as f64)).collect())?.to_sql(ty, out)
+            }
+            (Numbers::Ints(ints), postgres_types::Type::FLOAT8) => {
+                ints.iter().map(|v| v.map(|v| v as f64)).collect::<Vec<_>>().to_sql(ty, out)
+            }
+            (Numbers::Floats(floats), postgres_types::Type::FLOAT4) => reals(floats.clone())?.to_sql(ty, out),
+            (Numbers::Floats(floats), postgres_types::Type::FLOAT8) => floats.to_sql(ty, out),
+            // Anything else gets the usual type mismatch error
+            (Numbers::Ints(ints), _) => ints.to_sql_checked(ty, out),
+            (Numbers::Floats(floats), _) => floats.to_sql_checked(ty, out),
+        }
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool {
+        matches!(ty.kind(), postgres_types::Kind::Array(_))
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// Whether a float survives the round trip through PostgreSQL REAL unchanged
+fn real_is_exact(value: f64) -> bool {
+    value.is_nan() || (value as f32) as f64 == value
+}
+
+/// Extract a list of booleans (None elements allowed) for binding as BOOL[]
+///
+/// Returns `None` when the object isn't a non-empty list whose non-None
+/// elements are all booleans.
+fn extract_bool_list(obj: &PyAny) -> PyResult<Option<Vec<Option<bool>>>> {
+    extract_typed_list(obj, |item| Ok(item.downcast::<PyBool>().ok().map(|b| b.is_true())))
+}
+
+/// Extract a list of `uuid.UUID` (None elements allowed) for binding as UUID[],
+/// keeping the list's element order
+fn extract_uuid_list(py: Python, obj: &PyAny) -> PyResult<Option<Vec<Option<Uuid>>>> {
+    extract_typed_list(obj, |item| extract_uuid(py, item))
+}
+
+/// Extract a non-empty list whose non-None elements are all accepted by `element`
+///
+/// `element` returns `Ok(None)` for objects of the wrong type. Lists made up
+/// only of None are rejected, since their element type can't be inferred.
+fn extract_typed_list<T>(
+    obj: &PyAny,
+    element: impl Fn(&PyAny) -> PyResult<Option<T>>,
+) -> PyResult<Option<Vec<Option<T>>>> {
+    let list = match obj.downcast::<PyList>() {
+        Ok(list) if !list.is_empty() => list,
+        _ => return Ok(None),
+    };
+
+    let mut values = Vec::with_capacity(list.len());
+    let mut has_value = false;
+    for item in list {
+        if item.is_none() {
+            values.push(None);
+        } else if let Some(value) = element(item)? {
+            values.push(Some(value));
+            has_value = true;
+        } else {
+            return Ok(None);
+        }
+    }
+
+    Ok(if has_value { Some(values) } else { None })
+}
+
 /// High-performance batch conversion using SmallVec
 pub fn py_objects_to_postgres_values_fast(
     py: Python,
@@ -256,4 +1914,508 @@ mod tests {
             _ => panic!("Expected Bool variant"),
         }
     }
+
+    #[test]
+    fn test_timedelta_micros_negative_offsets() {
+        // UTC+05:30
+        assert_eq!(timedelta_micros(0, 19_800, 0), 19_800_000_000);
+        // UTC-05:00 normalized by Python as days=-1, seconds=68400
+        assert_eq!(timedelta_micros(-1, 68_400, 0), -18_000_000_000);
+        // UTC-00:00:00.5
+        assert_eq!(timedelta_micros(-1, 86_399, 500_000), -500_000);
+    }
+
+    #[test]
+    fn test_aware_datetimes_bind_as_utc_instant() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let bind = |expr: &str| {
+                let locals = PyDict::new(py);
+                py.run("import datetime, zoneinfo", None, Some(locals)).unwrap();
+                let value: PyObject = py.eval(expr, None, Some(locals)).unwrap().into();
+                let params = py_objects_to_postgres_values(py, &[value], ConversionOptions::default()).unwrap();
+                let mut buf = bytes::BytesMut::new();
+                params[0].to_sql_checked(&postgres_types::Type::TIMESTAMPTZ, &mut buf).unwrap();
+                DateTime::<Utc>::from_sql(&postgres_types::Type::TIMESTAMPTZ, &buf).unwrap()
+            };
+            let utc = |month, hour| NaiveDate::from_ymd_opt(2024, month, 15).unwrap().and_hms_opt(hour, 30, 0).unwrap().and_utc();
+
+            // Fixed offset west of UTC, stored by Python as days=-1
+            let fixed = "datetime.datetime(2024, 1, 15, 7, 30, tzinfo=datetime.timezone(datetime.timedelta(hours=-5)))";
+            assert_eq!(bind(fixed), utc(1, 12));
+
+            // zoneinfo has no fixed offset; utcoffset() resolves it for the date
+            let winter = "datetime.datetime(2024, 1, 15, 7, 30, tzinfo=zoneinfo.ZoneInfo('America/New_York'))";
+            assert_eq!(bind(winter), utc(1, 12));
+            let summer = "datetime.datetime(2024, 7, 15, 7, 30, tzinfo=zoneinfo.ZoneInfo('America/New_York'))";
+            assert_eq!(bind(summer), utc(7, 11));
+        });
+    }
+
+    #[test]
+    fn test_tid_decode() {
+        let raw = [0, 0, 1, 0, 0, 7];
+        let tid = Tid::from_sql(&postgres_types::Type::TID, &raw).unwrap();
+        assert_eq!(tid, Tid { block: 256, offset: 7 });
+
+        assert!(Tid::from_sql(&postgres_types::Type::TID, &raw[..4]).is_err());
+    }
+
+    #[test]
+    fn test_uuid_value_binding() {
+        let uuid = Uuid::from_u128(0x6ba7_b810_9dad_11d1_80b4_00c0_4fd4_30c8);
+        let value = PostgresValue::Uuid(uuid.into_bytes());
+
+        let mut buf = bytes::BytesMut::new();
+        value.to_sql_checked(&postgres_types::Type::UUID, &mut buf).unwrap();
+        assert_eq!(Uuid::from_sql(&postgres_types::Type::UUID, &buf).unwrap(), uuid);
+
+        // Still type-checked against the target
+        let mut buf = bytes::BytesMut::new();
+        assert!(value.to_sql_checked(&postgres_types::Type::INT4, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_uuid_array_round_trip_keeps_order() {
+        // Deliberately not in sorted order
+        let uuids = vec![
+            Some(Uuid::from_u128(0xffff_0000_0000_0000_0000_0000_0000_0003)),
+            Some(Uuid::from_u128(0x0000_0000_0000_0000_0000_0000_0000_0001)),
+            None,
+            Some(Uuid::from_u128(0x8000_0000_0000_0000_0000_0000_0000_0002)),
+            Some(Uuid::from_u128(0x0000_0000_0000_0000_0000_0000_0000_0001)),
+        ];
+
+        let mut buf = bytes::BytesMut::new();
+        uuids.to_sql(&postgres_types::Type::UUID_ARRAY, &mut buf).unwrap();
+        let decoded = Vec::<Option<Uuid>>::from_sql(&postgres_types::Type::UUID_ARRAY, &buf).unwrap();
+
+        assert_eq!(decoded, uuids);
+    }
+
+    #[test]
+    fn test_null_array_elements() {
+        let int_array = postgres_types::Type::INT4_ARRAY;
+
+        // All-None lists bind as NULL elements of whatever array type is expected
+        let all_null = vec![PostgresValue::Null; 2];
+        let mut buf = bytes::BytesMut::new();
+        all_null.to_sql_checked(&int_array, &mut buf).unwrap();
+        let decoded = Vec::<Option<i32>>::from_sql(&int_array, &buf).unwrap();
+        assert_eq!(decoded, vec![None, None]);
+
+        let single_null = vec![Some(1), None, Some(3)];
+        let mut buf = bytes::BytesMut::new();
+        single_null.to_sql_checked(&int_array, &mut buf).unwrap();
+        let decoded = Vec::<Option<i32>>::from_sql(&int_array, &buf).unwrap();
+        assert_eq!(decoded, single_null);
+    }
+
+    #[test]
+    fn test_bool_array_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let bool_array = postgres_types::Type::BOOL_ARRAY;
+            let round_trip = |expr: &str| {
+                let list = py.eval(expr, None, None).unwrap();
+                let values = extract_bool_list(list).unwrap().unwrap();
+                let mut buf = bytes::BytesMut::new();
+                values.to_sql_checked(&bool_array, &mut buf).unwrap();
+                let decoded = Vec::<Option<bool>>::from_sql(&bool_array, &buf).unwrap();
+                assert_eq!(decoded, values);
+                decoded
+            };
+
+            assert_eq!(round_trip("[True, True, True]"), vec![Some(true); 3]);
+            assert_eq!(round_trip("[False, False]"), vec![Some(false); 2]);
+            assert_eq!(round_trip("[True, False, True]"), vec![Some(true), Some(false), Some(true)]);
+            assert_eq!(round_trip("[None, True, None, False]"), vec![None, Some(true), None, Some(false)]);
+
+            // Ints aren't booleans, and all-None lists have no element type
+            assert!(extract_bool_list(py.eval("[True, 1]", None, None).unwrap()).unwrap().is_none());
+            assert!(extract_bool_list(py.eval("[None]", None, None).unwrap()).unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_numeric_decode() {
+        fn numeric(ndigits: u16, weight: i16, sign: u16, dscale: u16, digits: &[u16]) -> String {
+            let mut raw = Vec::new();
+            for word in [ndigits, weight as u16, sign, dscale].iter().chain(digits) {
+                raw.extend_from_slice(&word.to_be_bytes());
+            }
+            NumericText::from_sql(&postgres_types::Type::NUMERIC, &raw).unwrap().0
+        }
+
+        // 12345.678 = [1, 2345, 6780], weight 1
+        assert_eq!(numeric(3, 1, 0, 3, &[1, 2345, 6780]), "12345.678");
+        // -0.0012 = [12], weight -1
+        assert_eq!(numeric(1, -1, 0x4000, 4, &[12]), "-0.0012");
+        // 1000000 = [100], weight 1 (trailing zero groups are omitted)
+        assert_eq!(numeric(1, 1, 0, 0, &[100]), "1000000");
+        assert_eq!(numeric(0, 0, 0, 2, &[]), "0.00");
+        assert_eq!(numeric(0, 0, 0xC000, 0, &[]), "NaN");
+    }
+
+    #[test]
+    fn test_numeric_encode_round_trip() {
+        fn round_trip(text: &str) -> String {
+            let mut buf = bytes::BytesMut::new();
+            write_numeric(text, &mut buf).unwrap();
+            NumericText::from_sql(&postgres_types::Type::NUMERIC, &buf).unwrap().0
+        }
+
+        for text in ["12345.678", "-0.0012", "1000000", "0.00", "10.50", "0.000000001", "NaN", "-Infinity"] {
+            assert_eq!(round_trip(text), text);
+        }
+        // Leading zeros and the sign of zero are normalized
+        assert_eq!(round_trip("007.5"), "7.5");
+        assert_eq!(round_trip("-0.0"), "0.0");
+        assert_eq!(round_trip(".25"), "0.25");
+
+        let mut buf = bytes::BytesMut::new();
+        write_numeric("1000000", &mut buf).unwrap();
+        // [100], weight 1: trailing zero groups are dropped
+        assert_eq!(&buf[..], &[0, 1, 0, 1, 0, 0, 0, 0, 0, 100]);
+        assert!(write_numeric("1e5", &mut buf).is_err());
+        assert!(write_numeric("-", &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_numeric_array_special_values() {
+        // ARRAY[1.50, 'NaN', NULL, -0.001, 'Infinity']::numeric[] bound from
+        // Decimals and None, then decoded element by element as on fetch
+        let numeric_array = postgres_types::Type::NUMERIC_ARRAY;
+        let values = vec![
+            PostgresValue::Numeric("1.50".to_string()),
+            PostgresValue::Numeric("NaN".to_string()),
+            PostgresValue::Null,
+            PostgresValue::Numeric("-0.001".to_string()),
+            PostgresValue::Numeric("Infinity".to_string()),
+        ];
+        let mut buf = bytes::BytesMut::new();
+        values.to_sql_checked(&numeric_array, &mut buf).unwrap();
+
+        let decoded = Vec::<Option<NumericText>>::from_sql(&numeric_array, &buf).unwrap();
+        let decoded: Vec<Option<&str>> = decoded.iter().map(|n| n.as_ref().map(|n| n.0.as_str())).collect();
+        assert_eq!(decoded, vec![Some("1.50"), Some("NaN"), None, Some("-0.001"), Some("Infinity")]);
+
+        // Leading NULLs and an all-NaN tail keep their positions
+        let values = vec![PostgresValue::Null, PostgresValue::Numeric("NaN".to_string()), PostgresValue::Numeric("NaN".to_string())];
+        let mut buf = bytes::BytesMut::new();
+        values.to_sql_checked(&numeric_array, &mut buf).unwrap();
+        let decoded = Vec::<Option<NumericText>>::from_sql(&numeric_array, &buf).unwrap();
+        assert_eq!(decoded, vec![None, Some(NumericText("NaN".to_string())), Some(NumericText("NaN".to_string()))]);
+    }
+
+    #[test]
+    fn test_number_array_follows_element_type() {
+        let ints = |values: Vec<Option<i64>>| NumberArray { numbers: Numbers::Ints(values), strict: false };
+        let mut buf = bytes::BytesMut::new();
+
+        ints(vec![Some(1), None]).to_sql_checked(&postgres_types::Type::INT2_ARRAY, &mut buf).unwrap();
+        assert_eq!(
+            Vec::<Option<i16>>::from_sql(&postgres_types::Type::INT2_ARRAY, &buf).unwrap(),
+            vec![Some(1), None]
+        );
+        assert!(ints(vec![Some(70_000)]).to_sql_checked(&postgres_types::Type::INT2_ARRAY, &mut buf).is_err());
+
+        buf.clear();
+        ints(vec![Some(3)]).to_sql_checked(&postgres_types::Type::FLOAT8_ARRAY, &mut buf).unwrap();
+        assert_eq!(Vec::<f64>::from_sql(&postgres_types::Type::FLOAT8_ARRAY, &buf).unwrap(), vec![3.0]);
+
+        let floats = |strict| NumberArray { numbers: Numbers::Floats(vec![Some(0.1)]), strict };
+        buf.clear();
+        floats(false).to_sql_checked(&postgres_types::Type::FLOAT8_ARRAY, &mut buf).unwrap();
+        assert_eq!(Vec::<f64>::from_sql(&postgres_types::Type::FLOAT8_ARRAY, &buf).unwrap(), vec![0.1]);
+        assert!(floats(false).to_sql_checked(&postgres_types::Type::FLOAT4_ARRAY, &mut buf).is_ok());
+        assert!(floats(true).to_sql_checked(&postgres_types::Type::FLOAT4_ARRAY, &mut buf).is_err());
+        assert!(floats(false).to_sql_checked(&postgres_types::Type::TEXT_ARRAY, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_int_binds_to_smallint_parameter() {
+        // INSERT INTO t (small) VALUES ($1) with a small Python int
+        let mut buf = bytes::BytesMut::new();
+        int_value(5).to_sql_checked(&postgres_types::Type::INT2, &mut buf).unwrap();
+        assert_eq!(i16::from_sql(&postgres_types::Type::INT2, &buf).unwrap(), 5);
+        assert!(int_value(70_000).to_sql_checked(&postgres_types::Type::INT2, &mut buf).is_err());
+
+        buf.clear();
+        int_value(5).to_sql_checked(&postgres_types::Type::INT8, &mut buf).unwrap();
+        assert_eq!(i64::from_sql(&postgres_types::Type::INT8, &buf).unwrap(), 5);
+
+        buf.clear();
+        int_value(1 << 40).to_sql_checked(&postgres_types::Type::FLOAT8, &mut buf).unwrap();
+        assert_eq!(f64::from_sql(&postgres_types::Type::FLOAT8, &buf).unwrap(), (1u64 << 40) as f64);
+
+        buf.clear();
+        int_value(-1234).to_sql_checked(&postgres_types::Type::NUMERIC, &mut buf).unwrap();
+        assert_eq!(NumericText::from_sql(&postgres_types::Type::NUMERIC, &buf).unwrap().0, "-1234");
+
+        assert!(int_value(-1).to_sql_checked(&postgres_types::Type::OID, &mut buf).is_err());
+        assert!(int_value(1).to_sql_checked(&postgres_types::Type::TEXT, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_big_int_binds_by_parameter_type() {
+        let big = PostgresValue::Numeric("1267650600228229401496703205376".to_string());
+        let mut buf = bytes::BytesMut::new();
+        big.to_sql_checked(&postgres_types::Type::NUMERIC, &mut buf).unwrap();
+        assert_eq!(
+            NumericText::from_sql(&postgres_types::Type::NUMERIC, &buf).unwrap().0,
+            "1267650600228229401496703205376"
+        );
+
+        buf.clear();
+        big.to_sql_checked(&postgres_types::Type::FLOAT8, &mut buf).unwrap();
+        assert_eq!(f64::from_sql(&postgres_types::Type::FLOAT8, &buf).unwrap(), 2f64.powi(100));
+
+        let err = big.to_sql_checked(&postgres_types::Type::INT8, &mut buf).err().unwrap();
+        assert!(err.is::<ConversionError>());
+
+        // Integral decimals fit integer parameters; fractions don't
+        buf.clear();
+        PostgresValue::Numeric("-7".to_string()).to_sql_checked(&postgres_types::Type::INT2, &mut buf).unwrap();
+        assert_eq!(i16::from_sql(&postgres_types::Type::INT2, &buf).unwrap(), -7);
+        assert!(PostgresValue::Numeric("1.5".to_string())
+            .to_sql_checked(&postgres_types::Type::INT4, &mut buf)
+            .is_err());
+        assert!(!is_integral("NaN"));
+        assert!(!is_integral("-"));
+    }
+
+    #[test]
+    fn test_numeric_decodes_to_decimal_unless_numeric_int() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let type_name = |text: &str, numeric_int: bool| {
+                let options = ConversionOptions { numeric_int, ..Default::default() };
+                let value = numeric_to_py(py, text, options).unwrap();
+                value.as_ref(py).get_type().name().unwrap().to_string()
+            };
+
+            // Whole values of an unconstrained column stay Decimal, like the rest
+            assert_eq!(type_name("1", false), "Decimal");
+            assert_eq!(type_name("1.5", false), "Decimal");
+            assert_eq!(type_name("1267650600228229401496703205376", false), "Decimal");
+
+            assert_eq!(type_name("1", true), "int");
+            assert_eq!(type_name("-1267650600228229401496703205376", true), "int");
+            assert_eq!(type_name("1.0", true), "Decimal");
+            assert_eq!(type_name("NaN", true), "Decimal");
+        });
+    }
+
+    #[test]
+    fn test_str_binds_to_json_parameters() {
+        // data @> $1::jsonb with already-serialized JSON
+        let doc = PostgresValue::String("{\"a\": 1}".to_string());
+        let mut buf = bytes::BytesMut::new();
+        doc.to_sql_checked(&postgres_types::Type::JSONB, &mut buf).unwrap();
+        assert_eq!(JsonText::from_sql(&postgres_types::Type::JSONB, &buf).unwrap().0, "{\"a\": 1}");
+
+        buf.clear();
+        doc.to_sql_checked(&postgres_types::Type::TEXT, &mut buf).unwrap();
+        assert_eq!(&buf[..], b"{\"a\": 1}");
+
+        buf.clear();
+        PostgresValue::String("$.a".to_string())
+            .to_sql_checked(&postgres_types::Type::JSONPATH, &mut buf)
+            .unwrap();
+        assert_eq!(&buf[..], b"\x01$.a");
+        assert!(doc.to_sql_checked(&postgres_types::Type::INT4, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_float_param_keeps_double_precision() {
+        let sum = FloatParam { value: 0.1 + 0.2, strict: true };
+        let mut buf = bytes::BytesMut::new();
+        sum.to_sql_checked(&postgres_types::Type::FLOAT8, &mut buf).unwrap();
+        assert_eq!(f64::from_sql(&postgres_types::Type::FLOAT8, &buf).unwrap(), 0.1 + 0.2);
+
+        // Narrowed only for REAL, where strict mode rejects the lost precision
+        assert!(sum.to_sql_checked(&postgres_types::Type::FLOAT4, &mut buf).is_err());
+        buf.clear();
+        FloatParam { value: 0.1, strict: false }
+            .to_sql_checked(&postgres_types::Type::FLOAT4, &mut buf)
+            .unwrap();
+        assert_eq!(f32::from_sql(&postgres_types::Type::FLOAT4, &buf).unwrap(), 0.1f32);
+    }
+
+    #[test]
+    fn test_array_dims() {
+        let mut buf = bytes::BytesMut::new();
+        vec![Some(1i32), None].to_sql(&postgres_types::Type::INT4_ARRAY, &mut buf).unwrap();
+        assert_eq!(ArrayDims::from_sql(&postgres_types::Type::INT4_ARRAY, &buf).unwrap(), ArrayDims(1));
+
+        // '{{1},{2}}': ndim 2, no nulls, int4 elements
+        let mut raw = Vec::new();
+        for word in [2i32, 0, 23, 2, 1, 1, 1] {
+            raw.extend_from_slice(&word.to_be_bytes());
+        }
+        assert_eq!(ArrayDims::from_sql(&postgres_types::Type::INT4_ARRAY, &raw).unwrap(), ArrayDims(2));
+        assert!(ArrayDims::from_sql(&postgres_types::Type::INT4_ARRAY, &raw[..2]).is_err());
+
+        assert!(ArrayDims::accepts(&postgres_types::Type::TEXT_ARRAY));
+        assert!(!ArrayDims::accepts(&postgres_types::Type::TEXT));
+    }
+
+    #[test]
+    fn test_text_types_cover_arrays() {
+        let types = TextTypes { json: false, jsonb: true, bytea: true };
+        assert!(types.includes(&postgres_types::Type::JSONB));
+        assert!(types.includes(&postgres_types::Type::JSONB_ARRAY));
+        assert!(types.includes(&postgres_types::Type::BYTEA_ARRAY));
+        assert!(!types.includes(&postgres_types::Type::JSON));
+        assert!(!types.includes(&postgres_types::Type::TEXT));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let blob: Vec<u8> = (0..1 << 20).map(|i| (i * 31 % 256) as u8).collect();
+        let mut buf = bytes::BytesMut::new();
+        PostgresValue::Bytes(blob.clone())
+            .to_sql_checked(&postgres_types::Type::BYTEA, &mut buf)
+            .unwrap();
+        assert_eq!(<&[u8]>::from_sql(&postgres_types::Type::BYTEA, &buf).unwrap(), &blob[..]);
+        assert!(PostgresValue::Bytes(blob).to_sql_checked(&postgres_types::Type::TEXT, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_bytes_bind_to_uuid_parameter() {
+        let raw: Vec<u8> = (1..=16).collect();
+        let mut buf = bytes::BytesMut::new();
+        PostgresValue::Bytes(raw.clone())
+            .to_sql_checked(&postgres_types::Type::UUID, &mut buf)
+            .unwrap();
+        assert_eq!(Uuid::from_sql(&postgres_types::Type::UUID, &buf).unwrap().as_bytes()[..], raw[..]);
+
+        // The same 16 bytes stay bytes for a BYTEA parameter
+        buf.clear();
+        PostgresValue::Bytes(raw.clone())
+            .to_sql_checked(&postgres_types::Type::BYTEA, &mut buf)
+            .unwrap();
+        assert_eq!(&buf[..], &raw[..]);
+
+        let err = PostgresValue::Bytes(raw[..15].to_vec())
+            .to_sql_checked(&postgres_types::Type::UUID, &mut buf)
+            .err()
+            .unwrap();
+        assert!(err.is::<ConversionError>());
+    }
+
+    #[test]
+    fn test_temporal_infinities() {
+        let (min, max) = (py_datetime_min(), py_datetime_max());
+        let decode = |ty: &postgres_types::Type, raw: &[u8]| {
+            Temporal::<NaiveDateTime>::from_sql(ty, raw).map(|t| t.bounded(min, max))
+        };
+
+        let mut buf = bytes::BytesMut::new();
+        Temporal::from_bounded(max, min, max).to_sql(&postgres_types::Type::TIMESTAMP, &mut buf).unwrap();
+        assert_eq!(&buf[..], &i64::MAX.to_be_bytes());
+        assert_eq!(decode(&postgres_types::Type::TIMESTAMP, &buf).unwrap(), max);
+        assert_eq!(decode(&postgres_types::Type::TIMESTAMPTZ, &i64::MIN.to_be_bytes()).unwrap(), min);
+
+        // A finite value round-trips through chrono's encoding
+        let dt = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_micro_opt(12, 30, 0, 5).unwrap();
+        buf.clear();
+        Temporal::from_bounded(dt, min, max).to_sql(&postgres_types::Type::TIMESTAMP, &mut buf).unwrap();
+        assert_eq!(decode(&postgres_types::Type::TIMESTAMP, &buf).unwrap(), dt);
+
+        // Year 10000 doesn't fit a Python datetime
+        let late = NaiveDate::from_ymd_opt(10000, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        buf.clear();
+        late.to_sql(&postgres_types::Type::TIMESTAMP, &mut buf).unwrap();
+        assert!(decode(&postgres_types::Type::TIMESTAMP, &buf).is_err());
+
+        buf.clear();
+        Temporal::<NaiveDate>::NegInfinity.to_sql(&postgres_types::Type::DATE, &mut buf).unwrap();
+        assert_eq!(&buf[..], &i32::MIN.to_be_bytes());
+        assert_eq!(
+            Temporal::<NaiveDate>::from_sql(&postgres_types::Type::DATE, &buf).unwrap(),
+            Temporal::NegInfinity
+        );
+    }
+
+    #[test]
+    fn test_untyped_text() {
+        assert_eq!(float_text(0.1), "0.1");
+        assert_eq!(float_text(1e300), "1e300");
+        assert_eq!(float_text(-2.0), "-2.0");
+        assert_eq!(float_text(f64::NAN), "NaN");
+        assert_eq!(float_text(f64::NEG_INFINITY), "-Infinity");
+
+        let (min, max) = (py_datetime_min(), py_datetime_max());
+        let format = |dt: NaiveDateTime| dt.format("%Y-%m-%d %H:%M:%S%.f").to_string();
+        assert_eq!(Temporal::from_bounded(max, min, max).text(format), "infinity");
+        let dt = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_micro_opt(12, 30, 0, 5).unwrap();
+        assert_eq!(Temporal::from_bounded(dt, min, max).text(format), "2024-02-29 12:30:00.000005");
+
+        // Sent in the text format whatever type the server settles on
+        let value = UntypedText(Some("42".to_string()));
+        let mut buf = bytes::BytesMut::new();
+        assert!(matches!(value.encode_format(&postgres_types::Type::INT4), postgres_types::Format::Text));
+        value.to_sql_checked(&postgres_types::Type::INT4, &mut buf).unwrap();
+        assert_eq!(&buf[..], b"42");
+        assert!(matches!(
+            UntypedText(None).to_sql_checked(&postgres_types::Type::INT4, &mut buf),
+            Ok(postgres_types::IsNull::Yes)
+        ));
+    }
+
+    #[test]
+    fn test_column_typecode() {
+        assert_eq!(column_typecode(&postgres_types::Type::INT2), Some("h"));
+        assert_eq!(column_typecode(&postgres_types::Type::INT4), Some("i"));
+        assert_eq!(column_typecode(&postgres_types::Type::INT8), Some("q"));
+        assert_eq!(column_typecode(&postgres_types::Type::FLOAT4), Some("f"));
+        assert_eq!(column_typecode(&postgres_types::Type::FLOAT8), Some("d"));
+        assert_eq!(column_typecode(&postgres_types::Type::NUMERIC), None);
+        assert_eq!(column_typecode(&postgres_types::Type::INT4_ARRAY), None);
+    }
+
+    #[test]
+    fn test_interval_iso8601() {
+        let iso = |months, days, microseconds| Interval { months, days, microseconds }.to_iso8601();
+
+        assert_eq!(iso(14, 3, 14_706_000_000), "P1Y2M3DT4H5M6S");
+        assert_eq!(iso(0, 0, 0), "PT0S");
+        assert_eq!(iso(1, 0, 0), "P1M");
+        assert_eq!(iso(0, 0, 1_500_000), "PT1.5S");
+        assert_eq!(iso(-14, -3, -14_706_000_000), "P-1Y-2M-3DT-4H-5M-6S");
+        assert_eq!(iso(0, 1, -500_000), "P1DT-0.5S");
+    }
+
+    #[test]
+    fn test_real_is_exact() {
+        assert!(real_is_exact(0.5));
+        assert!(real_is_exact(f64::INFINITY));
+        assert!(real_is_exact(f64::NAN));
+        assert!(!real_is_exact(0.1));
+        assert!(!real_is_exact(1e40));
+    }
+
+    #[test]
+    fn test_enum_label_accepts() {
+        let mood = postgres_types::Type::new(
+            "mood".to_string(),
+            90000,
+            postgres_types::Kind::Enum(vec!["sad".to_string(), "happy".to_string()]),
+            "public".to_string(),
+        );
+        let moods = postgres_types::Type::new(
+            "_mood".to_string(),
+            90001,
+            postgres_types::Kind::Array(mood.clone()),
+            "public".to_string(),
+        );
+
+        assert!(EnumLabel::accepts(&mood));
+        assert!(!EnumLabel::accepts(&postgres_types::Type::TEXT));
+        assert!(<Vec<Option<EnumLabel>>>::accepts(&moods));
+        assert!(!<Vec<Option<EnumLabel>>>::accepts(&postgres_types::Type::TEXT_ARRAY));
+        assert_eq!(EnumLabel::from_sql(&mood, b"happy").unwrap(), EnumLabel("happy".to_string()));
+    }
 }